@@ -0,0 +1,13 @@
+//! Board support
+//!
+//! Each board module is enabled by its own feature (which also enables the feature of the chip
+//! mounted on the board), and describes the pins of the on-board devices.
+
+#[cfg(feature = "STM32L476G-DISCO")]
+pub mod stm32l476g_disco;
+
+#[cfg(feature = "NUCLEO-L476RG")]
+pub mod nucleo_l476rg;
+
+#[cfg(feature = "NUCLEO-L496ZG")]
+pub mod nucleo_l496zg;
@@ -0,0 +1,138 @@
+//! NUCLEO-L476RG board
+//!
+//! To use this module, enable the "NUCLEO-L476RG" feature.
+//!
+//! Pin assignment is taken from UM1724.
+//!
+//! ```rust
+//! let mut gpio_a = gpio::A::new(&mut rcc.ahb);
+//! let mut gpio_c = gpio::C::new(&mut rcc.ahb);
+//!
+//! let mut ld2 = Ld2::new(gpio_a.PA5.into_output(&mut gpio_a.moder, &mut gpio_a.otyper));
+//! let button = UserButton::new(gpio_c.PC13.into_input(&mut gpio_c.moder, &mut gpio_c.pupdr));
+//! let (tx, rx) = vcp(gpio_a.PA2, gpio_a.PA3, &mut gpio_a.moder, &mut gpio_a.otyper, &mut gpio_a.pupdr, &mut gpio_a.afrl);
+//! ```
+
+use core::ops::Deref;
+
+use hal::digital::InputPin;
+
+use exti::{GpioLine, Line};
+use gpio::{AFRL, Floating, Input, Led, MODER, OTYPER, Output, PUPDR, PullUp, PushPull, AF7};
+use gpio::{PA2, PA3, PA5, PC13};
+use stm32l4x6::GPIOA;
+
+define_led!(
+    /// User LED LD2 with Green color.
+    ///
+    /// Shares PA5 with Arduino D13.
+    Ld2,
+    PA5<Output<PushPull>>
+);
+
+/// Blue user button B1
+///
+/// The button has an external pull-up and reads low while pressed.
+pub struct UserButton(PC13<Input<Floating>>);
+impl UserButton {
+    /// EXTI line of the button, to be routed to port C
+    pub const LINE: Line = Line::Gpio(GpioLine(13));
+
+    /// Creates a new instance of the button.
+    pub fn new(pin: PC13<Input<Floating>>) -> Self {
+        UserButton(pin)
+    }
+
+    /// Returns whether the button is pressed
+    pub fn is_pressed(&self) -> bool {
+        self.0.is_low()
+    }
+
+    /// Releases the pin
+    pub fn free(self) -> PC13<Input<Floating>> {
+        self.0
+    }
+}
+
+impl Deref for UserButton {
+    type Target = PC13<Input<Floating>>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// TX pin of ST-LINK virtual COM port (USART2)
+pub type VcpTx = PA2<AF7>;
+/// RX pin of ST-LINK virtual COM port (USART2)
+///
+/// Pulled up, so that the line idles high while the ST-LINK is disconnected.
+pub type VcpRx = PA3<AF7<PushPull, PullUp>>;
+
+/// Configures the pins of ST-LINK virtual COM port for USART2
+pub fn vcp<TX, RX>(
+    tx: PA2<TX>,
+    rx: PA3<RX>,
+    moder: &mut MODER<GPIOA>,
+    otyper: &mut OTYPER<GPIOA>,
+    pupdr: &mut PUPDR<GPIOA>,
+    afrl: &mut AFRL<GPIOA>,
+) -> (VcpTx, VcpRx) {
+    (tx.into_alt_fun(moder, otyper, pupdr, afrl), rx.into_alt_fun(moder, otyper, pupdr, afrl))
+}
+
+/// Arduino Uno V3 connector (CN5, CN6, CN8 and CN9)
+///
+/// By default PA2/PA3 are wired to the ST-LINK only, they reach D1/D0 if SB62/SB63 are closed.
+pub mod arduino {
+    use gpio::{PA0, PA1, PA10, PA2, PA3, PA4, PA5, PA6, PA7, PA8, PA9};
+    use gpio::{PB0, PB10, PB3, PB4, PB5, PB6, PB8, PB9};
+    use gpio::{PC0, PC1, PC7};
+
+    /// A0
+    pub type A0<MODE> = PA0<MODE>;
+    /// A1
+    pub type A1<MODE> = PA1<MODE>;
+    /// A2
+    pub type A2<MODE> = PA4<MODE>;
+    /// A3
+    pub type A3<MODE> = PB0<MODE>;
+    /// A4
+    pub type A4<MODE> = PC1<MODE>;
+    /// A5
+    pub type A5<MODE> = PC0<MODE>;
+
+    /// D0 (USART2 RX)
+    pub type D0<MODE> = PA3<MODE>;
+    /// D1 (USART2 TX)
+    pub type D1<MODE> = PA2<MODE>;
+    /// D2
+    pub type D2<MODE> = PA10<MODE>;
+    /// D3
+    pub type D3<MODE> = PB3<MODE>;
+    /// D4
+    pub type D4<MODE> = PB5<MODE>;
+    /// D5
+    pub type D5<MODE> = PB4<MODE>;
+    /// D6
+    pub type D6<MODE> = PB10<MODE>;
+    /// D7
+    pub type D7<MODE> = PA8<MODE>;
+    /// D8
+    pub type D8<MODE> = PA9<MODE>;
+    /// D9
+    pub type D9<MODE> = PC7<MODE>;
+    /// D10 (SPI1 CS)
+    pub type D10<MODE> = PB6<MODE>;
+    /// D11 (SPI1 MOSI)
+    pub type D11<MODE> = PA7<MODE>;
+    /// D12 (SPI1 MISO)
+    pub type D12<MODE> = PA6<MODE>;
+    /// D13 (SPI1 SCK, LD2)
+    pub type D13<MODE> = PA5<MODE>;
+    /// D14 (I2C1 SDA)
+    pub type D14<MODE> = PB9<MODE>;
+    /// D15 (I2C1 SCL)
+    pub type D15<MODE> = PB8<MODE>;
+}
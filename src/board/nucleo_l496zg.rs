@@ -0,0 +1,161 @@
+//! NUCLEO-L496ZG board
+//!
+//! To use this module, enable the "NUCLEO-L496ZG" feature.
+//!
+//! Pin assignment is taken from UM2179.
+//!
+//! ```rust
+//! let mut gpio_b = gpio::B::new(&mut rcc.ahb);
+//! let mut gpio_c = gpio::C::new(&mut rcc.ahb);
+//! let mut gpio_g = gpio::G::new(&mut rcc.ahb);
+//!
+//! let mut ld1 = Ld1::new(gpio_c.PC7.into_output(&mut gpio_c.moder, &mut gpio_c.otyper));
+//! let button = UserButton::new(gpio_c.PC13.into_input(&mut gpio_c.moder, &mut gpio_c.pupdr));
+//! let (tx, rx) = vcp(gpio_g.PG7, gpio_g.PG8, &mut gpio_g.moder, &mut gpio_g.otyper, &mut gpio_g.pupdr,
+//!                    &mut gpio_g.afrl, &mut gpio_g.afrh, &mut pwr, &mut rcc.apb1);
+//! ```
+
+use core::ops::Deref;
+
+use hal::digital::InputPin;
+
+use exti::{GpioLine, Line};
+use gpio::{AFRH, AFRL, Floating, Input, Led, MODER, OTYPER, Output, PUPDR, PullUp, PushPull, AF8};
+use gpio::{PB14, PB7, PC13, PC7, PG7, PG8};
+use power::Power;
+use rcc::APB1;
+use stm32l4x6::GPIOG;
+
+define_led!(
+    /// User LED LD1 with Green color.
+    Ld1,
+    PC7<Output<PushPull>>
+);
+
+define_led!(
+    /// User LED LD2 with Blue color.
+    Ld2,
+    PB7<Output<PushPull>>
+);
+
+define_led!(
+    /// User LED LD3 with Red color.
+    Ld3,
+    PB14<Output<PushPull>>
+);
+
+/// Blue user button B1
+///
+/// The button has an external pull-down and reads high while pressed.
+pub struct UserButton(PC13<Input<Floating>>);
+impl UserButton {
+    /// EXTI line of the button, to be routed to port C
+    pub const LINE: Line = Line::Gpio(GpioLine(13));
+
+    /// Creates a new instance of the button.
+    pub fn new(pin: PC13<Input<Floating>>) -> Self {
+        UserButton(pin)
+    }
+
+    /// Returns whether the button is pressed
+    pub fn is_pressed(&self) -> bool {
+        self.0.is_high()
+    }
+
+    /// Releases the pin
+    pub fn free(self) -> PC13<Input<Floating>> {
+        self.0
+    }
+}
+
+impl Deref for UserButton {
+    type Target = PC13<Input<Floating>>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// TX pin of ST-LINK virtual COM port (LPUART1)
+pub type VcpTx = PG7<AF8>;
+/// RX pin of ST-LINK virtual COM port (LPUART1)
+///
+/// Pulled up, so that the line idles high while the ST-LINK is disconnected.
+pub type VcpRx = PG8<AF8<PushPull, PullUp>>;
+
+/// Configures the pins of ST-LINK virtual COM port for LPUART1
+///
+/// PG2 to PG15 are supplied by VDDIO2, so the supply is validated (PWR_CR2.IOSV) first.
+pub fn vcp<TX, RX>(
+    tx: PG7<TX>,
+    rx: PG8<RX>,
+    moder: &mut MODER<GPIOG>,
+    otyper: &mut OTYPER<GPIOG>,
+    pupdr: &mut PUPDR<GPIOG>,
+    afrl: &mut AFRL<GPIOG>,
+    afrh: &mut AFRH<GPIOG>,
+    pwr: &mut Power,
+    apb1: &mut APB1,
+) -> (VcpTx, VcpRx) {
+    apb1.enr1().modify(|_, w| w.pwren().set_bit());
+    pwr.cr2().modify(|_, w| w.iosv().set_bit());
+
+    (tx.into_alt_fun(moder, otyper, pupdr, afrl), rx.into_alt_fun(moder, otyper, pupdr, afrh))
+}
+
+/// Arduino Uno V3 part of the ST Zio connector (CN7, CN8, CN9 and CN10)
+pub mod arduino {
+    use gpio::{PA3, PA5, PA6, PA7};
+    use gpio::{PB8, PB9};
+    use gpio::{PC0, PC1, PC3, PC4, PC5};
+    use gpio::{PD14, PD15, PD8, PD9};
+    use gpio::{PE11, PE13, PE9};
+    use gpio::{PF12, PF13, PF14, PF15};
+
+    /// A0
+    pub type A0<MODE> = PA3<MODE>;
+    /// A1
+    pub type A1<MODE> = PC0<MODE>;
+    /// A2
+    pub type A2<MODE> = PC3<MODE>;
+    /// A3
+    pub type A3<MODE> = PC1<MODE>;
+    /// A4
+    pub type A4<MODE> = PC4<MODE>;
+    /// A5
+    pub type A5<MODE> = PC5<MODE>;
+
+    /// D0 (USART3 RX)
+    pub type D0<MODE> = PD9<MODE>;
+    /// D1 (USART3 TX)
+    pub type D1<MODE> = PD8<MODE>;
+    /// D2
+    pub type D2<MODE> = PF15<MODE>;
+    /// D3
+    pub type D3<MODE> = PE13<MODE>;
+    /// D4
+    pub type D4<MODE> = PF14<MODE>;
+    /// D5
+    pub type D5<MODE> = PE11<MODE>;
+    /// D6
+    pub type D6<MODE> = PE9<MODE>;
+    /// D7
+    pub type D7<MODE> = PF13<MODE>;
+    /// D8
+    pub type D8<MODE> = PF12<MODE>;
+    /// D9
+    pub type D9<MODE> = PD15<MODE>;
+    /// D10 (SPI1 CS)
+    pub type D10<MODE> = PD14<MODE>;
+    /// D11 (SPI1 MOSI)
+    pub type D11<MODE> = PA7<MODE>;
+    /// D12 (SPI1 MISO)
+    pub type D12<MODE> = PA6<MODE>;
+    /// D13 (SPI1 SCK)
+    pub type D13<MODE> = PA5<MODE>;
+    /// D14 (I2C1 SDA)
+    pub type D14<MODE> = PB9<MODE>;
+    /// D15 (I2C1 SCL)
+    pub type D15<MODE> = PB8<MODE>;
+}
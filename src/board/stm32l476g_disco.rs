@@ -0,0 +1,332 @@
+//! 32L476GDISCOVERY (STM32L476G-DISCO) board
+//!
+//! To use this module, enable the "STM32L476G-DISCO" feature.
+//!
+//! Pin assignment is taken from UM1879.
+//!
+//! ```rust
+//! let mut rcc = p.RCC.constrain();
+//! let mut exti = p.EXTI.constrain();
+//! let board = Board::take(
+//!     gpio::A::new(&mut rcc.ahb),
+//!     gpio::B::new(&mut rcc.ahb),
+//!     gpio::C::new(&mut rcc.ahb),
+//!     gpio::D::new(&mut rcc.ahb),
+//!     gpio::E::new(&mut rcc.ahb),
+//!     &mut exti,
+//!     &mut rcc.apb2,
+//! );
+//! ```
+
+use hal::digital::{InputPin, OutputPin};
+
+use exti::{Edge, Exti, GpioLine, Line, Signal};
+use gpio::stm32l476vg::gpio::{self, Floating, Input, OpenDrain, Output, PinSpeed, Port, PullDown, PullUp, PushPull};
+use gpio::stm32l476vg::gpio::{AF10, AF11, AF13, AF4, AF5, AF7};
+use gpio::stm32l476vg::gpio::{PA0, PA1, PA10, PA15, PA2, PA3, PA5, PA7, PA8, PA9};
+use gpio::stm32l476vg::gpio::{PB0, PB1, PB12, PB13, PB14, PB15, PB4, PB5, PB6, PB7, PB8, PB9};
+use gpio::stm32l476vg::gpio::{PC0, PC1, PC2, PC3, PC4, PC5, PC6, PC7, PC8};
+use gpio::stm32l476vg::gpio::{PD1, PD10, PD11, PD12, PD13, PD14, PD15, PD2, PD3, PD4, PD5, PD6, PD7, PD8, PD9};
+use gpio::stm32l476vg::gpio::{PE0, PE1, PE10, PE11, PE12, PE13, PE14, PE15, PE2, PE3, PE4, PE5, PE6};
+use gpio::stm32l476vg::led::{Led4, Led5};
+use rcc::APB2;
+
+/// Joystick button
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Button {
+    /// Center (select)
+    Center,
+    /// Left
+    Left,
+    /// Right
+    Right,
+    /// Up
+    Up,
+    /// Down
+    Down,
+}
+
+/// 4-direction joystick with selection
+///
+/// Buttons are pulled down and read high while pressed; their EXTI lines trigger on the rising
+/// edge.
+pub struct Joystick {
+    /// Center button
+    pub center: PA0<Input<PullDown>>,
+    /// Left button
+    pub left: PA1<Input<PullDown>>,
+    /// Right button
+    pub right: PA2<Input<PullDown>>,
+    /// Up button
+    pub up: PA3<Input<PullDown>>,
+    /// Down button
+    pub down: PA5<Input<PullDown>>,
+}
+
+impl Joystick {
+    /// EXTI lines of center, left, right, up and down buttons
+    pub const LINES: [Line; 5] = [
+        Line::Gpio(GpioLine(0)),
+        Line::Gpio(GpioLine(1)),
+        Line::Gpio(GpioLine(2)),
+        Line::Gpio(GpioLine(3)),
+        Line::Gpio(GpioLine(5)),
+    ];
+
+    /// Returns the button being pressed, if any.
+    pub fn pressed(&self) -> Option<Button> {
+        if self.center.is_high() {
+            Some(Button::Center)
+        } else if self.left.is_high() {
+            Some(Button::Left)
+        } else if self.right.is_high() {
+            Some(Button::Right)
+        } else if self.up.is_high() {
+            Some(Button::Up)
+        } else if self.down.is_high() {
+            Some(Button::Down)
+        } else {
+            None
+        }
+    }
+
+    /// Clears pending EXTI requests of all buttons.
+    pub fn clear_pending(&self, exti: &mut Exti) {
+        for line in Self::LINES.iter() {
+            let _ = exti.clear_pending(*line);
+        }
+    }
+}
+
+/// Segment and COM pins of the LCD glass (COM0-2 on PA8-PA10, COM3 on PB9)
+pub struct LcdPins {
+    /// Pins on GPIOA
+    pub a: (PA7<AF11>, PA8<AF11>, PA9<AF11>, PA10<AF11>, PA15<AF11>),
+    /// Pins on GPIOB
+    pub b: (PB0<AF11>, PB1<AF11>, PB4<AF11>, PB5<AF11>, PB9<AF11>, PB12<AF11>, PB13<AF11>, PB14<AF11>, PB15<AF11>),
+    /// Pins on GPIOC
+    pub c: (PC3<AF11>, PC4<AF11>, PC5<AF11>, PC6<AF11>, PC7<AF11>, PC8<AF11>),
+    /// Pins on GPIOD
+    pub d: (PD8<AF11>, PD9<AF11>, PD10<AF11>, PD11<AF11>, PD12<AF11>, PD13<AF11>, PD14<AF11>, PD15<AF11>),
+}
+
+/// CS43L22 audio DAC
+pub struct Audio {
+    /// I2C1 SCL
+    pub scl: PB6<AF4<OpenDrain, PullUp>>,
+    /// I2C1 SDA
+    pub sda: PB7<AF4<OpenDrain, PullUp>>,
+    /// Reset, active low
+    pub reset: PE3<Output<PushPull>>,
+    /// SAI1 master clock of block A
+    pub mclk: PE2<AF13>,
+    /// SAI1 frame synchronization of block A
+    pub fs: PE4<AF13>,
+    /// SAI1 bit clock of block A
+    pub sck: PE5<AF13>,
+    /// SAI1 data of block A
+    pub sd: PE6<AF13>,
+}
+
+/// L3GD20 gyroscope and LSM303C accelerometer/magnetometer on SPI2
+pub struct Mems {
+    /// SPI2 SCK
+    pub sck: PD1<AF5>,
+    /// SPI2 MISO
+    pub miso: PD3<AF5>,
+    /// SPI2 MOSI
+    pub mosi: PD4<AF5>,
+    /// Gyroscope chip select, active low
+    pub gyro_cs: PD7<Output<PushPull>>,
+    /// Gyroscope interrupt 1
+    pub gyro_int1: PD2<Input<Floating>>,
+    /// Gyroscope interrupt 2
+    pub gyro_int2: PB8<Input<Floating>>,
+    /// Accelerometer chip select, active low
+    pub xl_cs: PE0<Output<PushPull>>,
+    /// Accelerometer interrupt
+    pub xl_int: PE1<Input<Floating>>,
+    /// Magnetometer chip select, active low
+    pub mag_cs: PC0<Output<PushPull>>,
+    /// Magnetometer interrupt
+    pub mag_int: PC1<Input<Floating>>,
+    /// Magnetometer data ready
+    pub mag_drdy: PC2<Input<Floating>>,
+}
+
+/// N25Q128A Quad-SPI flash
+pub struct Qspi {
+    /// Clock
+    pub clk: PE10<AF10>,
+    /// Chip select
+    pub ncs: PE11<AF10>,
+    /// IO0
+    pub io0: PE12<AF10>,
+    /// IO1
+    pub io1: PE13<AF10>,
+    /// IO2
+    pub io2: PE14<AF10>,
+    /// IO3
+    pub io3: PE15<AF10>,
+}
+
+/// ST-LINK virtual COM port on USART2
+pub struct Vcp {
+    /// USART2 TX
+    pub tx: PD5<AF7>,
+    /// USART2 RX
+    pub rx: PD6<AF7<PushPull, PullUp>>,
+}
+
+/// On-board devices of the 32L476GDISCOVERY
+pub struct Board {
+    /// Red (LD4) and green (LD5) user LEDs
+    pub leds: (Led4, Led5),
+    /// Joystick
+    pub joystick: Joystick,
+    /// LCD glass
+    pub lcd: LcdPins,
+    /// Audio DAC
+    pub audio: Audio,
+    /// Motion sensors
+    pub mems: Mems,
+    /// Quad-SPI flash
+    pub qspi: Qspi,
+    /// ST-LINK virtual COM port
+    pub vcp: Vcp,
+}
+
+impl Board {
+    /// Takes the GPIO ports and configures the pins of all on-board devices.
+    ///
+    /// Joystick EXTI lines are routed to GPIOA and trigger on the rising edge with their
+    /// interrupts unmasked.
+    pub fn take(mut a: gpio::A, mut b: gpio::B, mut c: gpio::C, mut d: gpio::D, mut e: gpio::E, exti: &mut Exti, apb2: &mut APB2) -> Self {
+        let leds = (
+            Led4::new(b.PB2.into_output::<PushPull>(&mut b.moder, &mut b.otyper)),
+            Led5::new(e.PE8.into_output::<PushPull>(&mut e.moder, &mut e.otyper)),
+        );
+
+        let joystick = Joystick {
+            center: a.PA0.into_input::<PullDown>(&mut a.moder, &mut a.pupdr),
+            left: a.PA1.into_input::<PullDown>(&mut a.moder, &mut a.pupdr),
+            right: a.PA2.into_input::<PullDown>(&mut a.moder, &mut a.pupdr),
+            up: a.PA3.into_input::<PullDown>(&mut a.moder, &mut a.pupdr),
+            down: a.PA5.into_input::<PullDown>(&mut a.moder, &mut a.pupdr),
+        };
+        for line in Joystick::LINES.iter() {
+            let _ = exti.select_port(*line, Port::A, apb2);
+            let _ = exti.set_edge(*line, Edge::Rising);
+            exti.listen(*line, Signal::Interrupt);
+        }
+
+        macro_rules! lcd_pin {
+            ($port:ident, $PXi:ident, $AFR:ident) => {{
+                let pin = $port.$PXi.into_alt_fun::<AF11>(&mut $port.moder, &mut $port.otyper, &mut $port.pupdr, &mut $port.$AFR);
+                pin.set_pin_speed(PinSpeed::High, &mut $port.ospeedr);
+                pin
+            }};
+        }
+        let lcd = LcdPins {
+            a: (
+                lcd_pin!(a, PA7, afrl),
+                lcd_pin!(a, PA8, afrh),
+                lcd_pin!(a, PA9, afrh),
+                lcd_pin!(a, PA10, afrh),
+                lcd_pin!(a, PA15, afrh),
+            ),
+            b: (
+                lcd_pin!(b, PB0, afrl),
+                lcd_pin!(b, PB1, afrl),
+                lcd_pin!(b, PB4, afrl),
+                lcd_pin!(b, PB5, afrl),
+                lcd_pin!(b, PB9, afrh),
+                lcd_pin!(b, PB12, afrh),
+                lcd_pin!(b, PB13, afrh),
+                lcd_pin!(b, PB14, afrh),
+                lcd_pin!(b, PB15, afrh),
+            ),
+            c: (
+                lcd_pin!(c, PC3, afrl),
+                lcd_pin!(c, PC4, afrl),
+                lcd_pin!(c, PC5, afrl),
+                lcd_pin!(c, PC6, afrl),
+                lcd_pin!(c, PC7, afrl),
+                lcd_pin!(c, PC8, afrh),
+            ),
+            d: (
+                lcd_pin!(d, PD8, afrh),
+                lcd_pin!(d, PD9, afrh),
+                lcd_pin!(d, PD10, afrh),
+                lcd_pin!(d, PD11, afrh),
+                lcd_pin!(d, PD12, afrh),
+                lcd_pin!(d, PD13, afrh),
+                lcd_pin!(d, PD14, afrh),
+                lcd_pin!(d, PD15, afrh),
+            ),
+        };
+
+        let mut reset = e.PE3.into_output::<PushPull>(&mut e.moder, &mut e.otyper);
+        reset.set_low();
+        let audio = Audio {
+            scl: b.PB6.into_alt_fun(&mut b.moder, &mut b.otyper, &mut b.pupdr, &mut b.afrl),
+            sda: b.PB7.into_alt_fun(&mut b.moder, &mut b.otyper, &mut b.pupdr, &mut b.afrl),
+            reset,
+            mclk: e.PE2.into_alt_fun(&mut e.moder, &mut e.otyper, &mut e.pupdr, &mut e.afrl),
+            fs: e.PE4.into_alt_fun(&mut e.moder, &mut e.otyper, &mut e.pupdr, &mut e.afrl),
+            sck: e.PE5.into_alt_fun(&mut e.moder, &mut e.otyper, &mut e.pupdr, &mut e.afrl),
+            sd: e.PE6.into_alt_fun(&mut e.moder, &mut e.otyper, &mut e.pupdr, &mut e.afrl),
+        };
+
+        let mut gyro_cs = d.PD7.into_output::<PushPull>(&mut d.moder, &mut d.otyper);
+        gyro_cs.set_high();
+        let mut xl_cs = e.PE0.into_output::<PushPull>(&mut e.moder, &mut e.otyper);
+        xl_cs.set_high();
+        let mut mag_cs = c.PC0.into_output::<PushPull>(&mut c.moder, &mut c.otyper);
+        mag_cs.set_high();
+        let mems = Mems {
+            sck: d.PD1.into_alt_fun(&mut d.moder, &mut d.otyper, &mut d.pupdr, &mut d.afrl),
+            miso: d.PD3.into_alt_fun(&mut d.moder, &mut d.otyper, &mut d.pupdr, &mut d.afrl),
+            mosi: d.PD4.into_alt_fun(&mut d.moder, &mut d.otyper, &mut d.pupdr, &mut d.afrl),
+            gyro_cs,
+            gyro_int1: d.PD2.into_input::<Floating>(&mut d.moder, &mut d.pupdr),
+            gyro_int2: b.PB8.into_input::<Floating>(&mut b.moder, &mut b.pupdr),
+            xl_cs,
+            xl_int: e.PE1.into_input::<Floating>(&mut e.moder, &mut e.pupdr),
+            mag_cs,
+            mag_int: c.PC1.into_input::<Floating>(&mut c.moder, &mut c.pupdr),
+            mag_drdy: c.PC2.into_input::<Floating>(&mut c.moder, &mut c.pupdr),
+        };
+
+        macro_rules! qspi_pin {
+            ($PEi:ident) => {{
+                let pin = e.$PEi.into_alt_fun::<AF10>(&mut e.moder, &mut e.otyper, &mut e.pupdr, &mut e.afrh);
+                pin.set_pin_speed(PinSpeed::High, &mut e.ospeedr);
+                pin
+            }};
+        }
+        let qspi = Qspi {
+            clk: qspi_pin!(PE10),
+            ncs: qspi_pin!(PE11),
+            io0: qspi_pin!(PE12),
+            io1: qspi_pin!(PE13),
+            io2: qspi_pin!(PE14),
+            io3: qspi_pin!(PE15),
+        };
+
+        let vcp = Vcp {
+            tx: d.PD5.into_alt_fun(&mut d.moder, &mut d.otyper, &mut d.pupdr, &mut d.afrl),
+            rx: d.PD6.into_alt_fun(&mut d.moder, &mut d.otyper, &mut d.pupdr, &mut d.afrl),
+        };
+
+        Board {
+            leds,
+            joystick,
+            lcd,
+            audio,
+            mems,
+            qspi,
+            vcp,
+        }
+    }
+}
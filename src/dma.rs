@@ -200,6 +200,14 @@ macro_rules! dma {
                             unsafe { (*$DMAX::ptr()).$cndtrX.read().bits() }
                         }
 
+                        pub(crate) fn is_complete(&self) -> bool {
+                            self.isr().$tcifX().bit_is_set()
+                        }
+
+                        pub(crate) fn clear_flags(&self) {
+                            self.ifcr().write(|w| w.$cgifX().set_bit());
+                        }
+
                         pub fn set_req_map(&self, bits: u8) {
                             unsafe { (*$DMAX::ptr()).cselr.modify(|_,w| w.$cXs().bits(bits)) }
                         }
@@ -0,0 +1,331 @@
+//! Extended interrupts and events controller (EXTI)
+//!
+//! Besides the 16 GPIO lines, the EXTI routes wakeup signals of various peripherals to the NVIC
+//! (interrupt mask) and to the core's event input (event mask). Unmasking the event of a line
+//! allows to wake the core up from Stop mode with `WFE`, while unmasking the interrupt works with
+//! `WFI`.
+//!
+//! See Reference Manual Ch. 13.3
+//!
+//! ```rust
+//! let mut exti = p.EXTI.constrain();
+//! exti.listen(exti::Line::RtcWakeup, exti::Signal::Event);
+//! exti.set_edge(exti::Line::RtcWakeup, exti::Edge::Rising).unwrap();
+//! ```
+
+use stm32l4x6::{exti, EXTI, SYSCFG};
+
+use common::Constrain;
+use gpio::Port;
+use rcc::APB2;
+
+impl Constrain<Exti> for EXTI {
+    fn constrain(self) -> Exti {
+        Exti(())
+    }
+}
+
+/// EXTI error
+#[derive(Debug)]
+pub enum Error {
+    /// Operation is only available on configurable lines (see `Line::is_configurable`)
+    NotConfigurable,
+    /// Operation is only available on GPIO lines
+    NotGpio,
+    #[doc(hidden)] _Extensible,
+}
+
+/// Number of a GPIO line, from 0 to 15
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GpioLine(pub(crate) u8);
+
+impl GpioLine {
+    /// Returns the GPIO line `n`, or `None` if there is no such line
+    pub fn new(n: u8) -> Option<Self> {
+        match n < 16 {
+            true => Some(GpioLine(n)),
+            false => None,
+        }
+    }
+
+    /// Returns the number of the line
+    pub fn number(self) -> u8 {
+        self.0
+    }
+}
+
+/// EXTI line
+///
+/// Reference Manual Ch. 13.3 Table 59
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Line {
+    /// GPIO line 0 to 15, the port is selected via SYSCFG_EXTICRx
+    Gpio(GpioLine),
+    /// PVD output
+    Pvd,
+    /// OTG FS wakeup event
+    OtgFsWakeup,
+    /// RTC alarms
+    RtcAlarm,
+    /// RTC tamper, RTC timestamp or LSE CSS
+    RtcTamperTimeStampLseCss,
+    /// RTC wakeup timer
+    RtcWakeup,
+    /// COMP1 output
+    Comp1,
+    /// COMP2 output
+    Comp2,
+    /// I2C1 wakeup
+    I2c1Wakeup,
+    /// I2C2 wakeup
+    I2c2Wakeup,
+    /// I2C3 wakeup
+    I2c3Wakeup,
+    /// USART1 wakeup
+    Usart1Wakeup,
+    /// USART2 wakeup
+    Usart2Wakeup,
+    /// USART3 wakeup
+    Usart3Wakeup,
+    /// UART4 wakeup
+    Uart4Wakeup,
+    /// UART5 wakeup
+    Uart5Wakeup,
+    /// LPUART1 wakeup
+    Lpuart1Wakeup,
+    /// LPTIM1
+    Lptim1,
+    /// LPTIM2
+    Lptim2,
+    /// SWPMI1 wakeup
+    Swpmi1Wakeup,
+    /// PVM1 wakeup
+    Pvm1Wakeup,
+    /// PVM2 wakeup
+    Pvm2Wakeup,
+    /// PVM3 wakeup
+    Pvm3Wakeup,
+    /// PVM4 wakeup
+    Pvm4Wakeup,
+    /// LCD wakeup
+    LcdWakeup,
+    /// I2C4 wakeup
+    #[cfg(feature = "L496")]
+    I2c4Wakeup,
+}
+
+impl Line {
+    /// Returns the number of the line (0 to 40).
+    pub fn number(&self) -> u8 {
+        match *self {
+            Line::Gpio(line) => line.number(),
+            Line::Pvd => 16,
+            Line::OtgFsWakeup => 17,
+            Line::RtcAlarm => 18,
+            Line::RtcTamperTimeStampLseCss => 19,
+            Line::RtcWakeup => 20,
+            Line::Comp1 => 21,
+            Line::Comp2 => 22,
+            Line::I2c1Wakeup => 23,
+            Line::I2c2Wakeup => 24,
+            Line::I2c3Wakeup => 25,
+            Line::Usart1Wakeup => 26,
+            Line::Usart2Wakeup => 27,
+            Line::Usart3Wakeup => 28,
+            Line::Uart4Wakeup => 29,
+            Line::Uart5Wakeup => 30,
+            Line::Lpuart1Wakeup => 31,
+            Line::Lptim1 => 32,
+            Line::Lptim2 => 33,
+            Line::Swpmi1Wakeup => 34,
+            Line::Pvm1Wakeup => 35,
+            Line::Pvm2Wakeup => 36,
+            Line::Pvm3Wakeup => 37,
+            Line::Pvm4Wakeup => 38,
+            Line::LcdWakeup => 39,
+            #[cfg(feature = "L496")]
+            Line::I2c4Wakeup => 40,
+        }
+    }
+
+    /// Returns whether the line is configurable.
+    ///
+    /// Configurable lines have edge selection, software trigger and a pending bit. The remaining
+    /// (direct) lines are driven by their peripheral, which holds the status flag instead.
+    pub fn is_configurable(&self) -> bool {
+        matches!(self.number(), 0..=16 | 18..=22 | 35..=38)
+    }
+}
+
+/// Signal generated by the line
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Signal {
+    /// Interrupt request to the NVIC, wakes the core from `WFI`
+    Interrupt,
+    /// Event pulse to the core, wakes the core from `WFE`
+    Event,
+}
+
+/// Trigger edge of configurable line
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Edge {
+    /// Rising edge
+    Rising,
+    /// Falling edge
+    Falling,
+    /// Both rising and falling edges
+    RisingFalling,
+}
+
+#[inline]
+fn set_or_clear(original: u32, idx: u8, is_set: bool) -> u32 {
+    match is_set {
+        true => original | (1 << idx),
+        false => original & !(1 << idx),
+    }
+}
+
+// Lines 0 to 31 live in the registers with suffix 1, lines 32 to 40 in the ones with suffix 2.
+macro_rules! modify_line {
+    ($line:expr, $reg1:ident, $reg2:ident, $is_set:expr) => {{
+        let n = $line.number();
+        let exti = unsafe { &*EXTI::ptr() };
+        match n {
+            0..=31 => exti.$reg1.modify(|r, w| unsafe { w.bits(set_or_clear(r.bits(), n, $is_set)) }),
+            _ => exti.$reg2.modify(|r, w| unsafe { w.bits(set_or_clear(r.bits(), n - 32, $is_set)) }),
+        }
+    }};
+}
+
+// PR and SWIER bits are set by writing 1, writing 0 has no effect.
+macro_rules! write_line {
+    ($line:expr, $reg1:ident, $reg2:ident) => {{
+        let n = $line.number();
+        let exti = unsafe { &*EXTI::ptr() };
+        match n {
+            0..=31 => exti.$reg1.write(|w| unsafe { w.bits(1 << n) }),
+            _ => exti.$reg2.write(|w| unsafe { w.bits(1 << (n - 32)) }),
+        }
+    }};
+}
+
+/// Constrained EXTI peripheral
+pub struct Exti(());
+impl Exti {
+    /// Access EXTI register block
+    pub fn inner(&mut self) -> &exti::RegisterBlock {
+        unsafe { &*EXTI::ptr() }
+    }
+
+    /// Routes GPIO `line` to the pin with the same number of `port` (SYSCFG_EXTICRx).
+    ///
+    /// After reset all GPIO lines are routed to port A.
+    pub fn select_port(&mut self, line: Line, port: Port, apb2: &mut APB2) -> Result<(), Error> {
+        let n = match line {
+            Line::Gpio(_) => line.number(),
+            _ => return Err(Error::NotGpio),
+        };
+
+        apb2.enr().modify(|_, w| w.syscfgen().set_bit());
+
+        // Each EXTICRx holds 4 lines in 4-bit fields
+        let offset = (n % 4) * 4;
+        let bits = port as u32;
+        let syscfg = unsafe { &*SYSCFG::ptr() };
+        match n / 4 {
+            0 => syscfg.exticr1.modify(|r, w| unsafe { w.bits((r.bits() & !(0b1111 << offset)) | (bits << offset)) }),
+            1 => syscfg.exticr2.modify(|r, w| unsafe { w.bits((r.bits() & !(0b1111 << offset)) | (bits << offset)) }),
+            2 => syscfg.exticr3.modify(|r, w| unsafe { w.bits((r.bits() & !(0b1111 << offset)) | (bits << offset)) }),
+            _ => syscfg.exticr4.modify(|r, w| unsafe { w.bits((r.bits() & !(0b1111 << offset)) | (bits << offset)) }),
+        }
+
+        Ok(())
+    }
+
+    /// Unmasks `signal` of the `line`
+    pub fn listen(&mut self, line: Line, signal: Signal) {
+        match signal {
+            Signal::Interrupt => modify_line!(line, imr1, imr2, true),
+            Signal::Event => modify_line!(line, emr1, emr2, true),
+        }
+    }
+
+    /// Masks `signal` of the `line`
+    pub fn unlisten(&mut self, line: Line, signal: Signal) {
+        match signal {
+            Signal::Interrupt => modify_line!(line, imr1, imr2, false),
+            Signal::Event => modify_line!(line, emr1, emr2, false),
+        }
+    }
+
+    /// Returns whether `signal` of the `line` is unmasked
+    pub fn is_listening(&mut self, line: Line, signal: Signal) -> bool {
+        let n = line.number();
+        let bits = match (signal, n) {
+            (Signal::Interrupt, 0..=31) => self.inner().imr1.read().bits(),
+            (Signal::Interrupt, _) => self.inner().imr2.read().bits(),
+            (Signal::Event, 0..=31) => self.inner().emr1.read().bits(),
+            (Signal::Event, _) => self.inner().emr2.read().bits(),
+        };
+
+        bits & (1 << (n % 32)) != 0
+    }
+
+    /// Selects trigger edge(s) of configurable `line`
+    pub fn set_edge(&mut self, line: Line, edge: Edge) -> Result<(), Error> {
+        if !line.is_configurable() {
+            return Err(Error::NotConfigurable);
+        }
+
+        let (rising, falling) = match edge {
+            Edge::Rising => (true, false),
+            Edge::Falling => (false, true),
+            Edge::RisingFalling => (true, true),
+        };
+        modify_line!(line, rtsr1, rtsr2, rising);
+        modify_line!(line, ftsr1, ftsr2, falling);
+
+        Ok(())
+    }
+
+    /// Generates interrupt/event request on configurable `line` by software
+    ///
+    /// The request is only forwarded if the corresponding signal is unmasked.
+    pub fn trigger(&mut self, line: Line) -> Result<(), Error> {
+        if !line.is_configurable() {
+            return Err(Error::NotConfigurable);
+        }
+
+        write_line!(line, swier1, swier2);
+        Ok(())
+    }
+
+    /// Returns whether trigger request occurred on configurable `line`
+    ///
+    /// Direct lines always return `false` as their status is held by the peripheral.
+    pub fn is_pending(&mut self, line: Line) -> bool {
+        if !line.is_configurable() {
+            return false;
+        }
+
+        let n = line.number();
+        let bits = match n {
+            0..=31 => self.inner().pr1.read().bits(),
+            _ => self.inner().pr2.read().bits(),
+        };
+
+        bits & (1 << (n % 32)) != 0
+    }
+
+    /// Clears pending bit of configurable `line`
+    ///
+    /// Must be done in the interrupt handler, otherwise the interrupt is raised again.
+    pub fn clear_pending(&mut self, line: Line) -> Result<(), Error> {
+        if !line.is_configurable() {
+            return Err(Error::NotConfigurable);
+        }
+
+        write_line!(line, pr1, pr2);
+        Ok(())
+    }
+}
@@ -0,0 +1,356 @@
+//! Alternate function mapping
+//!
+//! Marker traits telling which pin, in which alternate function, carries a peripheral signal.
+//! Peripheral constructors bound their pin parameters with these traits, so that a pin in the
+//! wrong alternate function is rejected at compile time.
+//!
+//! The mapping is generated by `tools/af_table.py` from `tools/af_table.csv`, a transcription of
+//! the alternate function tables of the datasheets (DS10198 for the STM32L476xx/486xx, DS11585
+//! for the STM32L496xx/4A6xx), and only the pins of the selected package are covered. Signals of
+//! peripherals the PAC does not describe (I2C4, CAN2, DCMI, ...) as well as SYS, FMC, DFSDM, TSC,
+//! COMP and EVENTOUT functions are left out.
+//!
+//! The traits for the U(S)ART signals are `serial::{TxPin, RxPin, CtsPin, RtsPin, CkPin}`.
+
+#[cfg(feature = "L476")]
+use stm32l4x6::LCD;
+use stm32l4x6::{CAN, OTG_FS_GLOBAL, QUADSPI, SAI1, SAI2, SDMMC};
+use stm32l4x6::{I2C1, I2C2, I2C3, SPI1, SPI2, SPI3};
+use stm32l4x6::{TIM1, TIM15, TIM16, TIM17, TIM2, TIM3, TIM4, TIM5, TIM8};
+use stm32l4x6::{LPUART1, UART4, UART5, USART1, USART2, USART3};
+
+use serial;
+
+use super::*;
+
+/// Serial peripheral interface
+pub mod spi {
+    /// SCK pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the SCK signal of `SPI` in its alternate function.
+    pub unsafe trait SckPin<SPI> {}
+    /// MISO pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the MISO signal of `SPI` in its alternate function.
+    pub unsafe trait MisoPin<SPI> {}
+    /// MOSI pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the MOSI signal of `SPI` in its alternate function.
+    pub unsafe trait MosiPin<SPI> {}
+    /// NSS pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the NSS signal of `SPI` in its alternate function.
+    pub unsafe trait NssPin<SPI> {}
+}
+
+/// Inter-integrated circuit interface
+pub mod i2c {
+    /// SCL pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the SCL signal of `I2C` in its alternate function.
+    pub unsafe trait SclPin<I2C> {}
+    /// SDA pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the SDA signal of `I2C` in its alternate function.
+    pub unsafe trait SdaPin<I2C> {}
+    /// SMBus alert pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the sMBus alert signal of `I2C` in its alternate function.
+    pub unsafe trait SmbaPin<I2C> {}
+}
+
+/// Timers
+pub mod tim {
+    /// Channel 1 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the channel 1 signal of `TIM` in its alternate function.
+    pub unsafe trait Ch1Pin<TIM> {}
+    /// Channel 2 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the channel 2 signal of `TIM` in its alternate function.
+    pub unsafe trait Ch2Pin<TIM> {}
+    /// Channel 3 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the channel 3 signal of `TIM` in its alternate function.
+    pub unsafe trait Ch3Pin<TIM> {}
+    /// Channel 4 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the channel 4 signal of `TIM` in its alternate function.
+    pub unsafe trait Ch4Pin<TIM> {}
+    /// Complementary channel 1 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the complementary channel 1 signal of `TIM` in its alternate function.
+    pub unsafe trait Ch1NPin<TIM> {}
+    /// Complementary channel 2 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the complementary channel 2 signal of `TIM` in its alternate function.
+    pub unsafe trait Ch2NPin<TIM> {}
+    /// Complementary channel 3 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the complementary channel 3 signal of `TIM` in its alternate function.
+    pub unsafe trait Ch3NPin<TIM> {}
+    /// External trigger pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the external trigger signal of `TIM` in its alternate function.
+    pub unsafe trait EtrPin<TIM> {}
+    /// Break input pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the break input signal of `TIM` in its alternate function.
+    pub unsafe trait BkinPin<TIM> {}
+    /// Break input 2 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the break input 2 signal of `TIM` in its alternate function.
+    pub unsafe trait Bkin2Pin<TIM> {}
+}
+
+/// Serial audio interface
+pub mod sai {
+    /// Audio block A
+    pub struct BlockA;
+    /// Audio block B
+    pub struct BlockB;
+
+    /// MCLK pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the MCLK signal of `BLOCK` of `SAI` in its alternate function.
+    pub unsafe trait MclkPin<SAI, BLOCK> {}
+    /// FS pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the FS signal of `BLOCK` of `SAI` in its alternate function.
+    pub unsafe trait FsPin<SAI, BLOCK> {}
+    /// SCK pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the SCK signal of `BLOCK` of `SAI` in its alternate function.
+    pub unsafe trait SckPin<SAI, BLOCK> {}
+    /// SD pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the SD signal of `BLOCK` of `SAI` in its alternate function.
+    pub unsafe trait SdPin<SAI, BLOCK> {}
+}
+
+/// Quad-SPI interface (bank 1)
+pub mod qspi {
+    /// CLK pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the CLK signal of `QSPI` in its alternate function.
+    pub unsafe trait ClkPin<QSPI> {}
+    /// NCS pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the NCS signal of `QSPI` in its alternate function.
+    pub unsafe trait NcsPin<QSPI> {}
+    /// IO0 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the IO0 signal of `QSPI` in its alternate function.
+    pub unsafe trait Io0Pin<QSPI> {}
+    /// IO1 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the IO1 signal of `QSPI` in its alternate function.
+    pub unsafe trait Io1Pin<QSPI> {}
+    /// IO2 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the IO2 signal of `QSPI` in its alternate function.
+    pub unsafe trait Io2Pin<QSPI> {}
+    /// IO3 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the IO3 signal of `QSPI` in its alternate function.
+    pub unsafe trait Io3Pin<QSPI> {}
+}
+
+/// SD/SDIO/MMC card host interface
+pub mod sdmmc {
+    /// CK pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the CK signal of `SDMMC` in its alternate function.
+    pub unsafe trait CkPin<SDMMC> {}
+    /// CMD pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the CMD signal of `SDMMC` in its alternate function.
+    pub unsafe trait CmdPin<SDMMC> {}
+    /// D0 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D0 signal of `SDMMC` in its alternate function.
+    pub unsafe trait D0Pin<SDMMC> {}
+    /// D1 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D1 signal of `SDMMC` in its alternate function.
+    pub unsafe trait D1Pin<SDMMC> {}
+    /// D2 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D2 signal of `SDMMC` in its alternate function.
+    pub unsafe trait D2Pin<SDMMC> {}
+    /// D3 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D3 signal of `SDMMC` in its alternate function.
+    pub unsafe trait D3Pin<SDMMC> {}
+    /// D4 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D4 signal of `SDMMC` in its alternate function.
+    pub unsafe trait D4Pin<SDMMC> {}
+    /// D5 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D5 signal of `SDMMC` in its alternate function.
+    pub unsafe trait D5Pin<SDMMC> {}
+    /// D6 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D6 signal of `SDMMC` in its alternate function.
+    pub unsafe trait D6Pin<SDMMC> {}
+    /// D7 pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D7 signal of `SDMMC` in its alternate function.
+    pub unsafe trait D7Pin<SDMMC> {}
+}
+
+/// Controller area network
+pub mod can {
+    /// TX pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the TX signal of `CAN` in its alternate function.
+    pub unsafe trait TxPin<CAN> {}
+    /// RX pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the RX signal of `CAN` in its alternate function.
+    pub unsafe trait RxPin<CAN> {}
+}
+
+/// USB on-the-go full-speed
+pub mod usb {
+    /// D- pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D- signal of `USB` in its alternate function.
+    pub unsafe trait DmPin<USB> {}
+    /// D+ pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the D+ signal of `USB` in its alternate function.
+    pub unsafe trait DpPin<USB> {}
+    /// ID pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the ID signal of `USB` in its alternate function.
+    pub unsafe trait IdPin<USB> {}
+    /// SOF pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the SOF signal of `USB` in its alternate function.
+    pub unsafe trait SofPin<USB> {}
+    /// NOE pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the NOE signal of `USB` in its alternate function.
+    pub unsafe trait NoePin<USB> {}
+}
+
+/// Liquid crystal display controller (STM32L476xx/486xx only)
+pub mod lcd {
+    /// COM pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the COM signal of `LCD` in its alternate function.
+    pub unsafe trait ComPin<LCD> {}
+    /// SEG pin - DO NOT IMPLEMENT THIS TRAIT
+    ///
+    /// # Safety
+    ///
+    /// The pin must carry the SEG signal of `LCD` in its alternate function.
+    pub unsafe trait SegPin<LCD> {}
+}
+
+use self::sai::{BlockA, BlockB};
+
+// Each line lists the signals of a pin in one alternate function
+macro_rules! af_table {
+    ($($PXi:ident, $AF:ident: [$($($Trait:ident)::+ <$($P:ty),+>),+];)+) => {
+        $(
+            $(
+                unsafe impl<OUTPUT, PULL> $($Trait)::+ <$($P),+> for $PXi<$AF<OUTPUT, PULL>> {}
+            )+
+        )+
+    }
+}
+
+include!("af_table.rs");
@@ -0,0 +1,430 @@
+// Generated by tools/af_table.py from tools/af_table.csv, do not edit
+
+af_table! {
+    PA0, AF1: [tim::Ch1Pin<TIM2>];
+    PA0, AF2: [tim::Ch1Pin<TIM5>];
+    PA0, AF3: [tim::EtrPin<TIM8>];
+    PA0, AF7: [serial::CtsPin<USART2>];
+    PA0, AF8: [serial::TxPin<UART4>];
+    PA0, AF14: [tim::EtrPin<TIM2>];
+    PA1, AF1: [tim::Ch2Pin<TIM2>];
+    PA1, AF2: [tim::Ch2Pin<TIM5>];
+    PA1, AF7: [serial::RtsPin<USART2>];
+    PA1, AF8: [serial::RxPin<UART4>];
+    PA1, AF14: [tim::Ch1NPin<TIM15>];
+    PA2, AF1: [tim::Ch3Pin<TIM2>];
+    PA2, AF2: [tim::Ch3Pin<TIM5>];
+    PA2, AF7: [serial::TxPin<USART2>];
+    PA2, AF14: [tim::Ch1Pin<TIM15>];
+    PA3, AF1: [tim::Ch4Pin<TIM2>];
+    PA3, AF2: [tim::Ch4Pin<TIM5>];
+    PA3, AF7: [serial::RxPin<USART2>];
+    PA3, AF14: [tim::Ch2Pin<TIM15>];
+    PA4, AF5: [spi::NssPin<SPI1>];
+    PA4, AF6: [spi::NssPin<SPI3>];
+    PA4, AF7: [serial::CkPin<USART2>];
+    PA4, AF13: [sai::FsPin<SAI1, BlockB>];
+    PA5, AF1: [tim::Ch1Pin<TIM2>];
+    PA5, AF2: [tim::EtrPin<TIM2>];
+    PA5, AF3: [tim::Ch1NPin<TIM8>];
+    PA5, AF5: [spi::SckPin<SPI1>];
+    PA6, AF1: [tim::BkinPin<TIM1>];
+    PA6, AF2: [tim::Ch1Pin<TIM3>];
+    PA6, AF3: [tim::BkinPin<TIM8>];
+    PA6, AF5: [spi::MisoPin<SPI1>];
+    PA6, AF7: [serial::CtsPin<USART3>];
+    PA6, AF10: [qspi::Io3Pin<QUADSPI>];
+    PA6, AF14: [tim::Ch1Pin<TIM16>];
+    PA7, AF1: [tim::Ch1NPin<TIM1>];
+    PA7, AF2: [tim::Ch2Pin<TIM3>];
+    PA7, AF3: [tim::Ch1NPin<TIM8>];
+    PA7, AF5: [spi::MosiPin<SPI1>];
+    PA7, AF10: [qspi::Io2Pin<QUADSPI>];
+    PA7, AF14: [tim::Ch1Pin<TIM17>];
+    PA8, AF1: [tim::Ch1Pin<TIM1>];
+    PA8, AF7: [serial::CkPin<USART1>];
+    PA8, AF10: [usb::SofPin<OTG_FS_GLOBAL>];
+    PA9, AF1: [tim::Ch2Pin<TIM1>];
+    PA9, AF7: [serial::TxPin<USART1>];
+    PA9, AF14: [tim::BkinPin<TIM15>];
+    PA10, AF1: [tim::Ch3Pin<TIM1>];
+    PA10, AF7: [serial::RxPin<USART1>];
+    PA10, AF10: [usb::IdPin<OTG_FS_GLOBAL>];
+    PA10, AF14: [tim::BkinPin<TIM17>];
+    PA11, AF1: [tim::Ch4Pin<TIM1>];
+    PA11, AF2: [tim::Bkin2Pin<TIM1>];
+    PA11, AF7: [serial::CtsPin<USART1>];
+    PA11, AF9: [can::RxPin<CAN>];
+    PA11, AF10: [usb::DmPin<OTG_FS_GLOBAL>];
+    PA12, AF1: [tim::EtrPin<TIM1>];
+    PA12, AF7: [serial::RtsPin<USART1>];
+    PA12, AF9: [can::TxPin<CAN>];
+    PA12, AF10: [usb::DpPin<OTG_FS_GLOBAL>];
+    PA13, AF10: [usb::NoePin<OTG_FS_GLOBAL>];
+    PA15, AF1: [tim::Ch1Pin<TIM2>];
+    PA15, AF2: [tim::EtrPin<TIM2>];
+    PA15, AF3: [serial::RxPin<USART2>];
+    PA15, AF5: [spi::NssPin<SPI1>];
+    PA15, AF6: [spi::NssPin<SPI3>];
+    PA15, AF8: [serial::RtsPin<UART4>];
+    PA15, AF13: [sai::FsPin<SAI2, BlockB>];
+
+    PB0, AF1: [tim::Ch2NPin<TIM1>];
+    PB0, AF2: [tim::Ch3Pin<TIM3>];
+    PB0, AF3: [tim::Ch2NPin<TIM8>];
+    PB0, AF7: [serial::CkPin<USART3>];
+    PB0, AF10: [qspi::Io1Pin<QUADSPI>];
+    PB1, AF1: [tim::Ch3NPin<TIM1>];
+    PB1, AF2: [tim::Ch4Pin<TIM3>];
+    PB1, AF3: [tim::Ch3NPin<TIM8>];
+    PB1, AF7: [serial::RtsPin<USART3>];
+    PB1, AF10: [qspi::Io0Pin<QUADSPI>];
+    PB2, AF4: [i2c::SmbaPin<I2C3>];
+    PB3, AF1: [tim::Ch2Pin<TIM2>];
+    PB3, AF5: [spi::SckPin<SPI1>];
+    PB3, AF6: [spi::SckPin<SPI3>];
+    PB3, AF7: [serial::RtsPin<USART1>];
+    PB3, AF13: [sai::SckPin<SAI1, BlockB>];
+    PB4, AF2: [tim::Ch1Pin<TIM3>];
+    PB4, AF5: [spi::MisoPin<SPI1>];
+    PB4, AF6: [spi::MisoPin<SPI3>];
+    PB4, AF7: [serial::CtsPin<USART1>];
+    PB4, AF8: [serial::RtsPin<UART5>];
+    PB4, AF13: [sai::MclkPin<SAI1, BlockB>];
+    PB4, AF14: [tim::BkinPin<TIM17>];
+    PB5, AF2: [tim::Ch2Pin<TIM3>];
+    PB5, AF4: [i2c::SmbaPin<I2C1>];
+    PB5, AF5: [spi::MosiPin<SPI1>];
+    PB5, AF6: [spi::MosiPin<SPI3>];
+    PB5, AF7: [serial::CkPin<USART1>];
+    PB5, AF8: [serial::CtsPin<UART5>];
+    PB5, AF13: [sai::SdPin<SAI1, BlockB>];
+    PB5, AF14: [tim::BkinPin<TIM16>];
+    PB6, AF2: [tim::Ch1Pin<TIM4>];
+    PB6, AF3: [tim::Bkin2Pin<TIM8>];
+    PB6, AF4: [i2c::SclPin<I2C1>];
+    PB6, AF7: [serial::TxPin<USART1>];
+    PB6, AF13: [sai::FsPin<SAI1, BlockB>];
+    PB6, AF14: [tim::Ch1NPin<TIM16>];
+    PB7, AF2: [tim::Ch2Pin<TIM4>];
+    PB7, AF3: [tim::BkinPin<TIM8>];
+    PB7, AF4: [i2c::SdaPin<I2C1>];
+    PB7, AF7: [serial::RxPin<USART1>];
+    PB7, AF8: [serial::CtsPin<UART4>];
+    PB7, AF14: [tim::Ch1NPin<TIM17>];
+    PB8, AF2: [tim::Ch3Pin<TIM4>];
+    PB8, AF4: [i2c::SclPin<I2C1>];
+    PB8, AF9: [can::RxPin<CAN>];
+    PB8, AF12: [sdmmc::D4Pin<SDMMC>];
+    PB8, AF13: [sai::MclkPin<SAI1, BlockA>];
+    PB8, AF14: [tim::Ch1Pin<TIM16>];
+    PB9, AF2: [tim::Ch4Pin<TIM4>];
+    PB9, AF4: [i2c::SdaPin<I2C1>];
+    PB9, AF5: [spi::NssPin<SPI2>];
+    PB9, AF9: [can::TxPin<CAN>];
+    PB9, AF12: [sdmmc::D5Pin<SDMMC>];
+    PB9, AF13: [sai::FsPin<SAI1, BlockA>];
+    PB9, AF14: [tim::Ch1Pin<TIM17>];
+    PB10, AF1: [tim::Ch3Pin<TIM2>];
+    PB10, AF4: [i2c::SclPin<I2C2>];
+    PB10, AF5: [spi::SckPin<SPI2>];
+    PB10, AF7: [serial::TxPin<USART3>];
+    PB10, AF8: [serial::RxPin<LPUART1>];
+    PB10, AF10: [qspi::ClkPin<QUADSPI>];
+    PB10, AF13: [sai::SckPin<SAI1, BlockA>];
+    PB11, AF1: [tim::Ch4Pin<TIM2>];
+    PB11, AF4: [i2c::SdaPin<I2C2>];
+    PB11, AF7: [serial::RxPin<USART3>];
+    PB11, AF8: [serial::TxPin<LPUART1>];
+    PB11, AF10: [qspi::NcsPin<QUADSPI>];
+    PB12, AF1: [tim::BkinPin<TIM1>];
+    PB12, AF4: [i2c::SmbaPin<I2C2>];
+    PB12, AF5: [spi::NssPin<SPI2>];
+    PB12, AF7: [serial::CkPin<USART3>];
+    PB12, AF8: [serial::RtsPin<LPUART1>];
+    PB12, AF13: [sai::FsPin<SAI2, BlockA>];
+    PB12, AF14: [tim::BkinPin<TIM15>];
+    PB13, AF1: [tim::Ch1NPin<TIM1>];
+    PB13, AF4: [i2c::SclPin<I2C2>];
+    PB13, AF5: [spi::SckPin<SPI2>];
+    PB13, AF7: [serial::CtsPin<USART3>];
+    PB13, AF8: [serial::CtsPin<LPUART1>];
+    PB13, AF13: [sai::SckPin<SAI2, BlockA>];
+    PB13, AF14: [tim::Ch1NPin<TIM15>];
+    PB14, AF1: [tim::Ch2NPin<TIM1>];
+    PB14, AF3: [tim::Ch2NPin<TIM8>];
+    PB14, AF4: [i2c::SdaPin<I2C2>];
+    PB14, AF5: [spi::MisoPin<SPI2>];
+    PB14, AF7: [serial::RtsPin<USART3>];
+    PB14, AF13: [sai::MclkPin<SAI2, BlockA>];
+    PB14, AF14: [tim::Ch1Pin<TIM15>];
+    PB15, AF1: [tim::Ch3NPin<TIM1>];
+    PB15, AF3: [tim::Ch3NPin<TIM8>];
+    PB15, AF5: [spi::MosiPin<SPI2>];
+    PB15, AF13: [sai::SdPin<SAI2, BlockA>];
+    PB15, AF14: [tim::Ch2Pin<TIM15>];
+
+    PC0, AF4: [i2c::SclPin<I2C3>];
+    PC0, AF8: [serial::RxPin<LPUART1>];
+    PC1, AF4: [i2c::SdaPin<I2C3>];
+    PC1, AF8: [serial::TxPin<LPUART1>];
+    PC2, AF5: [spi::MisoPin<SPI2>];
+    PC3, AF5: [spi::MosiPin<SPI2>];
+    PC3, AF13: [sai::SdPin<SAI1, BlockA>];
+    PC4, AF7: [serial::TxPin<USART3>];
+    PC5, AF7: [serial::RxPin<USART3>];
+    PC6, AF2: [tim::Ch1Pin<TIM3>];
+    PC6, AF3: [tim::Ch1Pin<TIM8>];
+    PC6, AF12: [sdmmc::D6Pin<SDMMC>];
+    PC6, AF13: [sai::MclkPin<SAI2, BlockA>];
+    PC7, AF2: [tim::Ch2Pin<TIM3>];
+    PC7, AF3: [tim::Ch2Pin<TIM8>];
+    PC7, AF12: [sdmmc::D7Pin<SDMMC>];
+    PC7, AF13: [sai::MclkPin<SAI2, BlockB>];
+    PC8, AF2: [tim::Ch3Pin<TIM3>];
+    PC8, AF3: [tim::Ch3Pin<TIM8>];
+    PC8, AF12: [sdmmc::D0Pin<SDMMC>];
+    PC9, AF2: [tim::Ch4Pin<TIM3>];
+    PC9, AF3: [tim::Ch4Pin<TIM8>];
+    PC9, AF10: [usb::NoePin<OTG_FS_GLOBAL>];
+    PC9, AF12: [sdmmc::D1Pin<SDMMC>];
+    PC10, AF6: [spi::SckPin<SPI3>];
+    PC10, AF7: [serial::TxPin<USART3>];
+    PC10, AF8: [serial::TxPin<UART4>];
+    PC10, AF12: [sdmmc::D2Pin<SDMMC>];
+    PC10, AF13: [sai::SckPin<SAI2, BlockB>];
+    PC11, AF6: [spi::MisoPin<SPI3>];
+    PC11, AF7: [serial::RxPin<USART3>];
+    PC11, AF8: [serial::RxPin<UART4>];
+    PC11, AF12: [sdmmc::D3Pin<SDMMC>];
+    PC11, AF13: [sai::MclkPin<SAI2, BlockB>];
+    PC12, AF6: [spi::MosiPin<SPI3>];
+    PC12, AF7: [serial::CkPin<USART3>];
+    PC12, AF8: [serial::TxPin<UART5>];
+    PC12, AF12: [sdmmc::CkPin<SDMMC>];
+    PC12, AF13: [sai::SdPin<SAI2, BlockB>];
+}
+
+#[cfg(any(feature = "LQFP64", feature = "LQFP100", feature = "LQFP144", feature = "UFBGA169"))]
+af_table! {
+    PD2, AF2: [tim::EtrPin<TIM3>];
+    PD2, AF7: [serial::RtsPin<USART3>];
+    PD2, AF8: [serial::RxPin<UART5>];
+    PD2, AF12: [sdmmc::CmdPin<SDMMC>];
+}
+
+#[cfg(any(feature = "LQFP100", feature = "LQFP144", feature = "UFBGA169"))]
+af_table! {
+    PD0, AF5: [spi::NssPin<SPI2>];
+    PD0, AF9: [can::RxPin<CAN>];
+    PD1, AF5: [spi::SckPin<SPI2>];
+    PD1, AF9: [can::TxPin<CAN>];
+    PD3, AF5: [spi::MisoPin<SPI2>];
+    PD3, AF7: [serial::CtsPin<USART2>];
+    PD4, AF5: [spi::MosiPin<SPI2>];
+    PD4, AF7: [serial::RtsPin<USART2>];
+    PD5, AF7: [serial::TxPin<USART2>];
+    PD6, AF7: [serial::RxPin<USART2>];
+    PD6, AF13: [sai::SdPin<SAI1, BlockA>];
+    PD7, AF7: [serial::CkPin<USART2>];
+    PD8, AF7: [serial::TxPin<USART3>];
+    PD9, AF7: [serial::RxPin<USART3>];
+    PD9, AF13: [sai::MclkPin<SAI2, BlockA>];
+    PD10, AF7: [serial::CkPin<USART3>];
+    PD10, AF13: [sai::SckPin<SAI2, BlockA>];
+    PD11, AF7: [serial::CtsPin<USART3>];
+    PD11, AF13: [sai::SdPin<SAI2, BlockA>];
+    PD12, AF2: [tim::Ch1Pin<TIM4>];
+    PD12, AF7: [serial::RtsPin<USART3>];
+    PD12, AF13: [sai::FsPin<SAI2, BlockA>];
+    PD13, AF2: [tim::Ch2Pin<TIM4>];
+    PD14, AF2: [tim::Ch3Pin<TIM4>];
+    PD15, AF2: [tim::Ch4Pin<TIM4>];
+
+    PE0, AF2: [tim::EtrPin<TIM4>];
+    PE0, AF14: [tim::Ch1Pin<TIM16>];
+    PE1, AF14: [tim::Ch1Pin<TIM17>];
+    PE2, AF2: [tim::EtrPin<TIM3>];
+    PE2, AF13: [sai::MclkPin<SAI1, BlockA>];
+    PE3, AF2: [tim::Ch1Pin<TIM3>];
+    PE3, AF13: [sai::SdPin<SAI1, BlockB>];
+    PE4, AF2: [tim::Ch2Pin<TIM3>];
+    PE4, AF13: [sai::FsPin<SAI1, BlockA>];
+    PE5, AF2: [tim::Ch3Pin<TIM3>];
+    PE5, AF13: [sai::SckPin<SAI1, BlockA>];
+    PE6, AF2: [tim::Ch4Pin<TIM3>];
+    PE6, AF13: [sai::SdPin<SAI1, BlockA>];
+    PE7, AF1: [tim::EtrPin<TIM1>];
+    PE7, AF13: [sai::SdPin<SAI1, BlockB>];
+    PE8, AF1: [tim::Ch1NPin<TIM1>];
+    PE8, AF13: [sai::SckPin<SAI1, BlockB>];
+    PE9, AF1: [tim::Ch1Pin<TIM1>];
+    PE9, AF13: [sai::FsPin<SAI1, BlockB>];
+    PE10, AF1: [tim::Ch2NPin<TIM1>];
+    PE10, AF10: [qspi::ClkPin<QUADSPI>];
+    PE10, AF13: [sai::MclkPin<SAI1, BlockB>];
+    PE11, AF1: [tim::Ch2Pin<TIM1>];
+    PE11, AF10: [qspi::NcsPin<QUADSPI>];
+    PE12, AF1: [tim::Ch3NPin<TIM1>];
+    PE12, AF5: [spi::NssPin<SPI1>];
+    PE12, AF10: [qspi::Io0Pin<QUADSPI>];
+    PE13, AF1: [tim::Ch3Pin<TIM1>];
+    PE13, AF5: [spi::SckPin<SPI1>];
+    PE13, AF10: [qspi::Io1Pin<QUADSPI>];
+    PE14, AF1: [tim::Ch4Pin<TIM1>];
+    PE14, AF2: [tim::Bkin2Pin<TIM1>];
+    PE14, AF5: [spi::MisoPin<SPI1>];
+    PE14, AF10: [qspi::Io2Pin<QUADSPI>];
+    PE15, AF1: [tim::BkinPin<TIM1>];
+    PE15, AF5: [spi::MosiPin<SPI1>];
+    PE15, AF10: [qspi::Io3Pin<QUADSPI>];
+}
+
+#[cfg(any(feature = "LQFP144", feature = "UFBGA169"))]
+af_table! {
+    PF0, AF4: [i2c::SdaPin<I2C2>];
+    PF1, AF4: [i2c::SclPin<I2C2>];
+    PF2, AF4: [i2c::SmbaPin<I2C2>];
+    PF6, AF1: [tim::EtrPin<TIM5>];
+    PF6, AF2: [tim::Ch1Pin<TIM5>];
+    PF6, AF13: [sai::SdPin<SAI1, BlockB>];
+    PF7, AF2: [tim::Ch2Pin<TIM5>];
+    PF7, AF13: [sai::MclkPin<SAI1, BlockB>];
+    PF8, AF2: [tim::Ch3Pin<TIM5>];
+    PF8, AF13: [sai::SckPin<SAI1, BlockB>];
+    PF9, AF2: [tim::Ch4Pin<TIM5>];
+    PF9, AF13: [sai::FsPin<SAI1, BlockB>];
+    PF9, AF14: [tim::Ch1Pin<TIM15>];
+    PF10, AF14: [tim::Ch2Pin<TIM15>];
+
+    PG2, AF5: [spi::SckPin<SPI1>];
+    PG2, AF13: [sai::SckPin<SAI2, BlockB>];
+    PG3, AF5: [spi::MisoPin<SPI1>];
+    PG3, AF13: [sai::FsPin<SAI2, BlockB>];
+    PG4, AF5: [spi::MosiPin<SPI1>];
+    PG4, AF13: [sai::MclkPin<SAI2, BlockB>];
+    PG5, AF5: [spi::NssPin<SPI1>];
+    PG5, AF8: [serial::CtsPin<LPUART1>];
+    PG5, AF13: [sai::SdPin<SAI2, BlockB>];
+    PG6, AF4: [i2c::SmbaPin<I2C3>];
+    PG6, AF8: [serial::RtsPin<LPUART1>];
+    PG7, AF4: [i2c::SclPin<I2C3>];
+    PG7, AF8: [serial::TxPin<LPUART1>];
+    PG7, AF13: [sai::MclkPin<SAI1, BlockA>];
+    PG8, AF4: [i2c::SdaPin<I2C3>];
+    PG8, AF8: [serial::RxPin<LPUART1>];
+    PG9, AF6: [spi::SckPin<SPI3>];
+    PG9, AF7: [serial::TxPin<USART1>];
+    PG9, AF13: [sai::SckPin<SAI2, BlockA>];
+    PG9, AF14: [tim::Ch1NPin<TIM15>];
+    PG10, AF6: [spi::MisoPin<SPI3>];
+    PG10, AF7: [serial::RxPin<USART1>];
+    PG10, AF13: [sai::FsPin<SAI2, BlockA>];
+    PG10, AF14: [tim::Ch1Pin<TIM15>];
+    PG11, AF6: [spi::MosiPin<SPI3>];
+    PG11, AF7: [serial::CtsPin<USART1>];
+    PG11, AF13: [sai::MclkPin<SAI2, BlockA>];
+    PG11, AF14: [tim::Ch2Pin<TIM15>];
+    PG12, AF6: [spi::NssPin<SPI3>];
+    PG12, AF7: [serial::RtsPin<USART1>];
+    PG12, AF13: [sai::SdPin<SAI2, BlockA>];
+    PG13, AF4: [i2c::SdaPin<I2C1>];
+    PG13, AF7: [serial::CkPin<USART1>];
+    PG14, AF4: [i2c::SclPin<I2C1>];
+    PG15, AF4: [i2c::SmbaPin<I2C1>];
+}
+
+#[cfg(feature = "UFBGA169")]
+af_table! {
+    PH4, AF4: [i2c::SclPin<I2C2>];
+    PH5, AF4: [i2c::SdaPin<I2C2>];
+    PH6, AF4: [i2c::SmbaPin<I2C2>];
+    PH7, AF4: [i2c::SclPin<I2C3>];
+    PH8, AF4: [i2c::SdaPin<I2C3>];
+    PH9, AF4: [i2c::SmbaPin<I2C3>];
+    PH10, AF2: [tim::Ch1Pin<TIM5>];
+    PH11, AF2: [tim::Ch2Pin<TIM5>];
+    PH12, AF2: [tim::Ch3Pin<TIM5>];
+    PH13, AF3: [tim::Ch1NPin<TIM8>];
+    PH14, AF3: [tim::Ch2NPin<TIM8>];
+    PH15, AF3: [tim::Ch3NPin<TIM8>];
+}
+
+#[cfg(feature = "L476")]
+af_table! {
+    PA1, AF11: [lcd::SegPin<LCD>];
+    PA2, AF11: [lcd::SegPin<LCD>];
+    PA3, AF11: [lcd::SegPin<LCD>];
+    PA6, AF11: [lcd::SegPin<LCD>];
+    PA7, AF11: [lcd::SegPin<LCD>];
+    PA8, AF11: [lcd::ComPin<LCD>];
+    PA9, AF11: [lcd::ComPin<LCD>];
+    PA10, AF11: [lcd::ComPin<LCD>];
+    PA15, AF11: [lcd::SegPin<LCD>];
+
+    PB0, AF11: [lcd::SegPin<LCD>];
+    PB1, AF11: [lcd::SegPin<LCD>];
+    PB3, AF11: [lcd::SegPin<LCD>];
+    PB4, AF11: [lcd::SegPin<LCD>];
+    PB5, AF11: [lcd::SegPin<LCD>];
+    PB7, AF11: [lcd::SegPin<LCD>];
+    PB8, AF11: [lcd::SegPin<LCD>];
+    PB9, AF11: [lcd::ComPin<LCD>];
+    PB10, AF11: [lcd::SegPin<LCD>];
+    PB11, AF11: [lcd::SegPin<LCD>];
+    PB12, AF11: [lcd::SegPin<LCD>];
+    PB13, AF11: [lcd::SegPin<LCD>];
+    PB14, AF11: [lcd::SegPin<LCD>];
+    PB15, AF11: [lcd::SegPin<LCD>];
+
+    PC0, AF11: [lcd::SegPin<LCD>];
+    PC1, AF11: [lcd::SegPin<LCD>];
+    PC2, AF11: [lcd::SegPin<LCD>];
+    PC4, AF11: [lcd::SegPin<LCD>];
+    PC5, AF11: [lcd::SegPin<LCD>];
+    PC6, AF11: [lcd::SegPin<LCD>];
+    PC7, AF11: [lcd::SegPin<LCD>];
+    PC8, AF11: [lcd::SegPin<LCD>];
+    PC9, AF11: [lcd::SegPin<LCD>];
+    PC10, AF11: [lcd::ComPin<LCD>, lcd::SegPin<LCD>];
+    PC11, AF11: [lcd::ComPin<LCD>, lcd::SegPin<LCD>];
+    PC12, AF11: [lcd::ComPin<LCD>, lcd::SegPin<LCD>];
+
+    PD2, AF11: [lcd::ComPin<LCD>, lcd::SegPin<LCD>];
+}
+
+#[cfg(all(feature = "L476", any(feature = "LQFP100", feature = "LQFP144")))]
+af_table! {
+    PD8, AF11: [lcd::SegPin<LCD>];
+    PD9, AF11: [lcd::SegPin<LCD>];
+    PD10, AF11: [lcd::SegPin<LCD>];
+    PD11, AF11: [lcd::SegPin<LCD>];
+    PD12, AF11: [lcd::SegPin<LCD>];
+    PD13, AF11: [lcd::SegPin<LCD>];
+    PD14, AF11: [lcd::SegPin<LCD>];
+    PD15, AF11: [lcd::SegPin<LCD>];
+
+    PE0, AF11: [lcd::SegPin<LCD>];
+    PE1, AF11: [lcd::SegPin<LCD>];
+    PE2, AF11: [lcd::SegPin<LCD>];
+    PE3, AF11: [lcd::SegPin<LCD>];
+}
+
+#[cfg(feature = "L496")]
+af_table! {
+    PA1, AF4: [i2c::SmbaPin<I2C1>];
+    PA1, AF5: [spi::SckPin<SPI1>];
+    PA2, AF8: [serial::TxPin<LPUART1>];
+    PA3, AF8: [serial::RxPin<LPUART1>];
+    PA6, AF8: [serial::CtsPin<LPUART1>];
+    PA7, AF4: [i2c::SclPin<I2C3>];
+    PA9, AF4: [i2c::SclPin<I2C1>];
+    PA10, AF4: [i2c::SdaPin<I2C1>];
+    PA11, AF5: [spi::MisoPin<SPI1>];
+    PA12, AF5: [spi::MosiPin<SPI1>];
+    PA14, AF4: [i2c::SmbaPin<I2C1>];
+
+    PB0, AF5: [spi::NssPin<SPI1>];
+    PB1, AF8: [serial::RtsPin<LPUART1>];
+    PB4, AF4: [i2c::SdaPin<I2C3>];
+}
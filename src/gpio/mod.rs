@@ -1,14 +1,16 @@
 //! General Purpose Input / Output
 //!
 //! This module provides common GPIO definitions that are available on all STM32L4x6 packages. By
-//! enabling features, you can use definitions for specific chips which may include additional
-//! GPIO lines. In that case, you will probably not want to `use` this module directly, but instead
-//! use it re-exported by the chip module.
+//! enabling the feature of a chip, the additional GPIO lines of its package (see `ports`) are
+//! defined here as well.
 
+#[cfg(feature = "L476")]
+use core::ptr;
 use marker::PhantomData;
 use ops::Deref;
 
-use hal::digital::{toggleable, OutputPin, StatefulOutputPin};
+use cortex_m::interrupt;
+use hal::digital::{toggleable, v2, InputPin, OutputPin, StatefulOutputPin};
 
 use stm32l4x6;
 
@@ -80,95 +82,108 @@ pub struct Output<MODE> {
     _mode: PhantomData<MODE>,
 }
 
+/// Dynamic mode (type state)
+///
+/// Direction of the pin is switched at runtime, see `into_dynamic`.
+pub struct Dynamic;
+
+/// GPIO error
+#[derive(Debug)]
+pub enum Error {
+    /// Dynamic pin is used in the wrong direction
+    IncorrectMode,
+    #[doc(hidden)] _Extensible,
+}
+
+/// Analog mode (type state)
+///
+/// Drivers of analog peripherals (ADC, DAC, comparators, operational amplifiers) can demand pins
+/// in this state as proof of correct configuration.
+pub struct Analog;
+
 /// Alternate Function Trait
 /// Implemented only for corresponding structs.
 ///
 /// Note: MUST not be implemented by user.
 pub trait AltFun {
     const NUM: u32;
+    /// Output type of the pin
+    type Output: OutputMode;
+    /// Pull-up/pull-down of the pin
+    type Pull: InputMode;
 }
 
-/// Alternate function 0 (type state)
-pub struct AF0;
-impl AltFun for AF0 {
-    const NUM: u32 = 0;
-}
-/// Alternate function 1 (type state)
-pub struct AF1;
-impl AltFun for AF1 {
-    const NUM: u32 = 1;
-}
-/// Alternate function 2 (type state)
-pub struct AF2;
-impl AltFun for AF2 {
-    const NUM: u32 = 2;
-}
-/// Alternate function 3 (type state)
-pub struct AF3;
-impl AltFun for AF3 {
-    const NUM: u32 = 3;
-}
-/// Alternate function 4 (type state)
-pub struct AF4;
-impl AltFun for AF4 {
-    const NUM: u32 = 4;
-}
-/// Alternate function 5 (type state)
-pub struct AF5;
-impl AltFun for AF5 {
-    const NUM: u32 = 5;
-}
-/// Alternate function 6 (type state)
-pub struct AF6;
-impl AltFun for AF6 {
-    const NUM: u32 = 6;
-}
-/// Alternate function 7 (type state)
-pub struct AF7;
-impl AltFun for AF7 {
-    const NUM: u32 = 7;
-}
-/// Alternate function 8 (type state)
-pub struct AF8;
-impl AltFun for AF8 {
-    const NUM: u32 = 8;
-}
-/// Alternate function 9 (type state)
-pub struct AF9;
-impl AltFun for AF9 {
-    const NUM: u32 = 9;
-}
-/// Alternate function 10 (type state)
-pub struct AF10;
-impl AltFun for AF10 {
-    const NUM: u32 = 10;
-}
-/// Alternate function 11 (type state)
-pub struct AF11;
-impl AltFun for AF11 {
-    const NUM: u32 = 11;
-}
-/// Alternate function 12 (type state)
-pub struct AF12;
-impl AltFun for AF12 {
-    const NUM: u32 = 12;
-}
-/// Alternate function 13 (type state)
-pub struct AF13;
-impl AltFun for AF13 {
-    const NUM: u32 = 13;
-}
-/// Alternate function 14 (type state)
-pub struct AF14;
-impl AltFun for AF14 {
-    const NUM: u32 = 14;
-}
-/// Alternate function 15 (type state)
-pub struct AF15;
-impl AltFun for AF15 {
-    const NUM: u32 = 15;
+/// Same alternate function with output type `OUTPUT` and pull `PULL`.
+/// Implemented only for corresponding structs.
+///
+/// Note: MUST not be implemented by user.
+pub trait AltFunWith<OUTPUT, PULL>: AltFun {
+    type Target: AltFun<Output = OUTPUT, Pull = PULL>;
 }
 
+/// Pin in an alternate function, e.g. to bound its output type and pull.
+/// Implemented only for corresponding structs.
+///
+/// Note: MUST not be implemented by user.
+pub trait AltFunPin {
+    /// Alternate function of the pin
+    type AltFun: AltFun;
+}
+
+macro_rules! impl_alt_fun {
+    ($($(#[$attr:meta])* $AF:ident: $num:expr;)+) => {
+        $(
+            $(#[$attr])*
+            pub struct $AF<OUTPUT = PushPull, PULL = Floating> {
+                _mode: PhantomData<(OUTPUT, PULL)>,
+            }
+            impl<OUTPUT: OutputMode, PULL: InputMode> AltFun for $AF<OUTPUT, PULL> {
+                const NUM: u32 = $num;
+                type Output = OUTPUT;
+                type Pull = PULL;
+            }
+            impl<O: OutputMode, P: InputMode, OUTPUT: OutputMode, PULL: InputMode> AltFunWith<OUTPUT, PULL> for $AF<O, P> {
+                type Target = $AF<OUTPUT, PULL>;
+            }
+        )+
+    }
+}
+
+impl_alt_fun!(
+    /// Alternate function 0 (type state)
+    AF0: 0;
+    /// Alternate function 1 (type state)
+    AF1: 1;
+    /// Alternate function 2 (type state)
+    AF2: 2;
+    /// Alternate function 3 (type state)
+    AF3: 3;
+    /// Alternate function 4 (type state)
+    AF4: 4;
+    /// Alternate function 5 (type state)
+    AF5: 5;
+    /// Alternate function 6 (type state)
+    AF6: 6;
+    /// Alternate function 7 (type state)
+    AF7: 7;
+    /// Alternate function 8 (type state)
+    AF8: 8;
+    /// Alternate function 9 (type state)
+    AF9: 9;
+    /// Alternate function 10 (type state)
+    AF10: 10;
+    /// Alternate function 11 (type state)
+    AF11: 11;
+    /// Alternate function 12 (type state)
+    AF12: 12;
+    /// Alternate function 13 (type state)
+    AF13: 13;
+    /// Alternate function 14 (type state)
+    AF14: 14;
+    /// Alternate function 15 (type state)
+    AF15: 15;
+);
+
 #[repr(C)]
 pub enum PinSpeed {
     Low = 0,
@@ -181,6 +196,12 @@ macro_rules! impl_parts {
     ($($GPIOX:ident, $gpiox:ident;)+) => {
         $(
             use stm32l4x6::$GPIOX;
+            impl GpioPort for $GPIOX {
+                #[inline]
+                fn ptr() -> *const stm32l4x6::gpioc::RegisterBlock {
+                    $GPIOX::ptr() as *const _
+                }
+            }
             impl AFRL<$GPIOX> {
                 pub(crate) fn afr(&mut self) -> &stm32l4x6::$gpiox::AFRL {
                     unsafe { &(*$GPIOX::ptr()).afrl }
@@ -192,9 +213,28 @@ macro_rules! impl_parts {
                 }
             }
             impl MODER<$GPIOX> {
-                pub(crate) fn moder(&mut self) -> &stm32l4x6::$gpiox::MODER {
-                    unsafe { &(*$GPIOX::ptr()).moder }
+                /// Connects (or disconnects) the analog switch of pin `i` to the ADC input.
+                ///
+                /// GPIOx_ASCR exists only on STM32L47x/L48x (Reference Manual Ch. 8.5.12) and is not
+                /// described by the SVD, hence the access by offset. It is only ever modified together
+                /// with MODER, so holding `&mut MODER` guards it as well.
+                #[cfg(feature = "L476")]
+                pub(crate) fn set_analog_switch(&mut self, i: u8, is_on: bool) {
+                    let ascr = ($GPIOX::ptr() as usize + 0x2C) as *mut u32;
+                    unsafe {
+                        let bits = ptr::read_volatile(ascr);
+                        let bits = match is_on {
+                            true => bits | (1 << i),
+                            false => bits & !(1 << i),
+                        };
+                        ptr::write_volatile(ascr, bits);
+                    }
                 }
+
+                /// GPIOx_ASCR does not exist on this chip.
+                #[cfg(not(feature = "L476"))]
+                #[inline]
+                pub(crate) fn set_analog_switch(&mut self, _i: u8, _is_on: bool) {}
             }
             impl OTYPER<$GPIOX> {
                 pub(crate) fn otyper(&mut self) -> &stm32l4x6::$gpiox::OTYPER {
@@ -216,15 +256,79 @@ macro_rules! impl_parts {
 }
 
 macro_rules! impl_gpio {
-    ($name:ident, $GPIOX:ident, $gpioen:ident, $gpiorst:ident) => {
-        impl_gpio!($name, $GPIOX, $gpioen, $gpiorst, AFRL: [], AFRH: []);
+    ($name:ident, $GPIOX:ident, $gpioen:ident, $gpiorst:ident, $PXx:ident) => {
+        impl_gpio!($name, $GPIOX, $gpioen, $gpiorst, $PXx, AFRL: [], AFRH: []);
     };
-    ($name:ident, $GPIOX:ident, $gpioen:ident, $gpiorst:ident, AFRL: [$($PXiL:ident, $iL:expr;)*]) => {
-        impl_gpio!($name, $GPIOX, $gpioen, $gpiorst, AFRL: [$($PXiL, $iL;)*], AFRH: []);
+    ($name:ident, $GPIOX:ident, $gpioen:ident, $gpiorst:ident, $PXx:ident, AFRL: [$($PXiL:ident, $iL:expr;)*]) => {
+        impl_gpio!($name, $GPIOX, $gpioen, $gpiorst, $PXx, AFRL: [$($PXiL, $iL;)*], AFRH: []);
     };
-    ($name:ident, $GPIOX:ident, $gpioen:ident, $gpiorst:ident, AFRL: [$($PXiL:ident, $iL:expr;)*], AFRH: [$($PXiH:ident, $iH:expr;)*]) => {
-        impl_pins!($GPIOX, AFRL: [$($PXiL, $iL;)*]);
-        impl_pins!($GPIOX, AFRH: [$($PXiH, $iH;)*]);
+    ($name:ident, $GPIOX:ident, $gpioen:ident, $gpiorst:ident, $PXx:ident, AFRL: [$($PXiL:ident, $iL:expr;)*], AFRH: [$($PXiH:ident, $iH:expr;)*]) => {
+        impl_pins!($GPIOX, $PXx, AFRL: [$($PXiL, $iL;)*]);
+        impl_pins!($GPIOX, $PXx, AFRH: [$($PXiH, $iH;)*]);
+
+        /// Pin of this port with the pin number erased from the type
+        pub struct $PXx<MODE> {
+            i: u8,
+            _mode: PhantomData<MODE>,
+        }
+
+        impl<MODE> $PXx<MODE> {
+            /// Erases the port from the type.
+            pub fn downgrade(self) -> Pin<MODE> {
+                Pin {
+                    port: Port::$name,
+                    i: self.i,
+                    _mode: PhantomData,
+                }
+            }
+        }
+
+        impl<MODE> OutputPin for $PXx<Output<MODE>> {
+            /// Sets high bit.
+            fn set_high(&mut self) {
+                // NOTE(unsafe) atomic write to a stateless register
+                unsafe { (*$GPIOX::ptr()).bsrr.write(|w| w.bits(1 << self.i)) }
+            }
+
+            /// Sets low bit.
+            fn set_low(&mut self) {
+                // NOTE(unsafe) atomic write to a stateless register
+                unsafe { (*$GPIOX::ptr()).bsrr.write(|w| w.bits(1 << (16 + self.i))) }
+            }
+        }
+
+        impl<MODE> StatefulOutputPin for $PXx<Output<MODE>> {
+            /// Returns whether high bit is set.
+            fn is_set_high(&self) -> bool {
+                !self.is_set_low()
+            }
+
+            /// Returns whether low bit is set.
+            fn is_set_low(&self) -> bool {
+                // NOTE(unsafe) atomic read with no side effects
+                unsafe { (*$GPIOX::ptr()).odr.read().bits() & (1 << self.i) == 0 }
+            }
+        }
+
+        unsafe impl<MODE> PinSet<$GPIOX, MODE> for $PXx<MODE> {
+            #[inline]
+            fn mask(&self) -> u16 {
+                1 << self.i
+            }
+        }
+
+        impl<MODE> InputPin for $PXx<Input<MODE>> {
+            /// Returns whether input is high.
+            fn is_high(&self) -> bool {
+                !self.is_low()
+            }
+
+            /// Returns whether input is low.
+            fn is_low(&self) -> bool {
+                // NOTE(unsafe) atomic read with no side effects
+                unsafe { (*$GPIOX::ptr()).idr.read().bits() & (1 << self.i) == 0 }
+            }
+        }
 
         #[allow(non_snake_case)]
         ///GPIO
@@ -280,7 +384,7 @@ macro_rules! impl_gpio {
 }
 
 macro_rules! impl_pin {
-    ($GPIOX:ident, $PXi:ident, $AFR:ident, $i:expr) => {
+    ($GPIOX:ident, $PXx:ident, $PXi:ident, $AFR:ident, $i:expr) => {
         /// Specific Pin
         pub struct $PXi<MODE>(PhantomData<MODE>);
 
@@ -289,7 +393,8 @@ macro_rules! impl_pin {
 
             /// Configures the PIN to operate as Input Pin according to Mode.
             pub fn into_input<Mode: InputMode>(self, moder: &mut MODER<$GPIOX>, pupdr: &mut PUPDR<$GPIOX>) -> $PXi<Input<Mode>> {
-                moder.moder().modify(|r, w| unsafe { w.bits(r.bits() & !(0b11 << Self::OFFSET)) });
+                moder.set_analog_switch($i, false);
+                set_mode::<$GPIOX>(Self::OFFSET, 0b00);
                 pupdr.pupdr().modify(|r, w| unsafe { w.bits(Mode::modify_pupdr_bits(r.bits(), Self::OFFSET)) });
 
                 $PXi(PhantomData)
@@ -304,25 +409,169 @@ macro_rules! impl_pin {
 
             /// Configures the PIN to operate as Output Pin according to Mode.
             pub fn into_output<Mode: OutputMode>(self, moder: &mut MODER<$GPIOX>, otyper: &mut OTYPER<$GPIOX>) -> $PXi<Output<Mode>> {
-                moder
-                    .moder()
-                    .modify(|r, w| unsafe { w.bits((r.bits() & !(0b11 << Self::OFFSET)) | (0b01 << Self::OFFSET)) });
+                moder.set_analog_switch($i, false);
+                set_mode::<$GPIOX>(Self::OFFSET, 0b01);
                 otyper.otyper().modify(|r, w| unsafe { w.bits(Mode::modify_otyper_bits(r.bits(), $i)) });
 
                 $PXi(PhantomData)
             }
 
             /// Configures the PIN to operate as Alternate Function.
-            pub fn into_alt_fun<AF: AltFun>(self, moder: &mut MODER<$GPIOX>, afr: &mut $AFR<$GPIOX>) -> $PXi<AF> {
+            ///
+            /// Output type and pull are taken from the type state, e.g. `AF4<OpenDrain, PullUp>`.
+            /// By default alternate function is push-pull without pull-up/pull-down.
+            pub fn into_alt_fun<AF: AltFun>(
+                self,
+                moder: &mut MODER<$GPIOX>,
+                otyper: &mut OTYPER<$GPIOX>,
+                pupdr: &mut PUPDR<$GPIOX>,
+                afr: &mut $AFR<$GPIOX>,
+            ) -> $PXi<AF> {
                 // AFRx pin fields are 4 bits wide, and each 8-pin bank has its own reg (L or H); e.g. pin 8's offset is _0_, within AFRH.
                 const AFR_OFFSET: usize = ($i % 8) * 4;
-                moder
-                    .moder()
-                    .modify(|r, w| unsafe { w.bits((r.bits() & !(0b11 << Self::OFFSET)) | (0b10 << Self::OFFSET)) });
+                otyper.otyper().modify(|r, w| unsafe { w.bits(AF::Output::modify_otyper_bits(r.bits(), $i)) });
+                pupdr.pupdr().modify(|r, w| unsafe { w.bits(AF::Pull::modify_pupdr_bits(r.bits(), Self::OFFSET)) });
                 afr.afr().modify(|r, w| unsafe { w.bits((r.bits() & !(0b1111 << AFR_OFFSET)) | (AF::NUM << AFR_OFFSET)) });
+                moder.set_analog_switch($i, false);
+                set_mode::<$GPIOX>(Self::OFFSET, 0b10);
+
+                $PXi(PhantomData)
+            }
+
+            /// Configures the PIN to operate as Dynamic Pin, which starts as input.
+            ///
+            /// Output type and pull are fixed here, while direction is switched at runtime by
+            /// `make_input`/`make_output` without the need for `MODER`.
+            pub fn into_dynamic<OUTPUT: OutputMode, PULL: InputMode>(
+                self,
+                moder: &mut MODER<$GPIOX>,
+                otyper: &mut OTYPER<$GPIOX>,
+                pupdr: &mut PUPDR<$GPIOX>,
+            ) -> $PXi<Dynamic> {
+                otyper.otyper().modify(|r, w| unsafe { w.bits(OUTPUT::modify_otyper_bits(r.bits(), $i)) });
+                pupdr.pupdr().modify(|r, w| unsafe { w.bits(PULL::modify_pupdr_bits(r.bits(), Self::OFFSET)) });
+                moder.set_analog_switch($i, false);
+                set_mode::<$GPIOX>(Self::OFFSET, 0b00);
 
                 $PXi(PhantomData)
             }
+
+            /// Configures the PIN to operate as Analog Pin.
+            ///
+            /// Pull-up/pull-down is disabled and, on STM32L47x/L48x, the analog switch to the ADC is closed.
+            pub fn into_analog(self, moder: &mut MODER<$GPIOX>, pupdr: &mut PUPDR<$GPIOX>) -> $PXi<Analog> {
+                pupdr.pupdr().modify(|r, w| unsafe { w.bits(r.bits() & !(0b11 << Self::OFFSET)) });
+                set_mode::<$GPIOX>(Self::OFFSET, 0b11);
+                moder.set_analog_switch($i, true);
+
+                $PXi(PhantomData)
+            }
+
+            /// Locks configuration of the PIN until the next reset.
+            ///
+            /// Fails, returning the PIN back, if the port has been locked before without this PIN.
+            pub fn lock(self) -> Result<Locked<Self>, Self> {
+                match lock_pins::<$GPIOX>(1 << $i) {
+                    true => Ok(Locked(self)),
+                    false => Err(self),
+                }
+            }
+
+            /// Erases the pin number from the type.
+            ///
+            /// This is useful when you want to collect the pins of one port into an array.
+            pub fn downgrade(self) -> $PXx<MODE> {
+                $PXx {
+                    i: $i,
+                    _mode: PhantomData,
+                }
+            }
+        }
+
+        impl<AF: AltFun> AltFunPin for $PXi<AF> {
+            type AltFun = AF;
+        }
+
+        impl<AF: AltFun> $PXi<AF> {
+            /// Changes output type of the Alternate Function PIN.
+            pub fn into_output_type<OUTPUT: OutputMode>(self, otyper: &mut OTYPER<$GPIOX>) -> $PXi<<AF as AltFunWith<OUTPUT, AF::Pull>>::Target>
+            where
+                AF: AltFunWith<OUTPUT, <AF as AltFun>::Pull>,
+            {
+                otyper.otyper().modify(|r, w| unsafe { w.bits(OUTPUT::modify_otyper_bits(r.bits(), $i)) });
+
+                $PXi(PhantomData)
+            }
+
+            /// Changes pull-up/pull-down of the Alternate Function PIN.
+            pub fn into_pull<PULL: InputMode>(self, pupdr: &mut PUPDR<$GPIOX>) -> $PXi<<AF as AltFunWith<AF::Output, PULL>>::Target>
+            where
+                AF: AltFunWith<<AF as AltFun>::Output, PULL>,
+            {
+                pupdr.pupdr().modify(|r, w| unsafe { w.bits(PULL::modify_pupdr_bits(r.bits(), Self::OFFSET)) });
+
+                $PXi(PhantomData)
+            }
+        }
+
+        impl $PXi<Dynamic> {
+            /// Switches the PIN to input.
+            pub fn make_input(&mut self) {
+                set_mode::<$GPIOX>(Self::OFFSET, 0b00);
+            }
+
+            /// Switches the PIN to output.
+            pub fn make_output(&mut self) {
+                set_mode::<$GPIOX>(Self::OFFSET, 0b01);
+            }
+
+            /// Returns whether the PIN is currently input.
+            pub fn is_input(&self) -> bool {
+                // NOTE(unsafe) atomic read with no side effects
+                unsafe { (*$GPIOX::ptr()).moder.read().bits() & (0b11 << Self::OFFSET) == 0 }
+            }
+        }
+
+        impl v2::OutputPin for $PXi<Dynamic> {
+            type Error = Error;
+
+            /// Sets high bit, fails if the PIN is input.
+            fn set_high(&mut self) -> Result<(), Error> {
+                if self.is_input() {
+                    return Err(Error::IncorrectMode);
+                }
+                // NOTE(unsafe) atomic write to a stateless register
+                unsafe { (*$GPIOX::ptr()).bsrr.write(|w| w.bits(1 << $i)) }
+                Ok(())
+            }
+
+            /// Sets low bit, fails if the PIN is input.
+            fn set_low(&mut self) -> Result<(), Error> {
+                if self.is_input() {
+                    return Err(Error::IncorrectMode);
+                }
+                // NOTE(unsafe) atomic write to a stateless register
+                unsafe { (*$GPIOX::ptr()).bsrr.write(|w| w.bits(1 << (16 + $i))) }
+                Ok(())
+            }
+        }
+
+        impl v2::InputPin for $PXi<Dynamic> {
+            type Error = Error;
+
+            /// Returns whether input is high, fails if the PIN is output.
+            fn is_high(&self) -> Result<bool, Error> {
+                self.is_low().map(|is_low| !is_low)
+            }
+
+            /// Returns whether input is low, fails if the PIN is output.
+            fn is_low(&self) -> Result<bool, Error> {
+                if !self.is_input() {
+                    return Err(Error::IncorrectMode);
+                }
+                // NOTE(unsafe) atomic read with no side effects
+                Ok(unsafe { (*$GPIOX::ptr()).idr.read().bits() & (1 << $i) == 0 })
+            }
         }
 
         impl<MODE> OutputPin for $PXi<Output<MODE>> {
@@ -351,19 +600,366 @@ macro_rules! impl_pin {
                 unsafe { (*$GPIOX::ptr()).odr.read().bits() & (1 << $i) == 0 }
             }
         }
+
+        impl<MODE> InputPin for $PXi<Input<MODE>> {
+            /// Returns whether input is high.
+            fn is_high(&self) -> bool {
+                !self.is_low()
+            }
+
+            /// Returns whether input is low.
+            fn is_low(&self) -> bool {
+                // NOTE(unsafe) atomic read with no side effects
+                unsafe { (*$GPIOX::ptr()).idr.read().bits() & (1 << $i) == 0 }
+            }
+        }
+
+        unsafe impl<MODE> PinSet<$GPIOX, MODE> for $PXi<MODE> {
+            #[inline]
+            fn mask(&self) -> u16 {
+                1 << $i
+            }
+        }
     };
 }
 
 macro_rules! impl_pins {
-    ($GPIOX:ident, $ARF:ident: [$($PXi:ident, $i:expr;)*]) => {
+    ($GPIOX:ident, $PXx:ident, $ARF:ident: [$($PXi:ident, $i:expr;)*]) => {
         $(
-            impl_pin!($GPIOX, $PXi, $ARF, $i);
+            impl_pin!($GPIOX, $PXx, $PXi, $ARF, $i);
          )*
     }
 }
 
+/// GPIO port
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Port {
+    /// GPIOA
+    A = 0,
+    /// GPIOB
+    B,
+    /// GPIOC
+    C,
+    /// GPIOD
+    D,
+    /// GPIOE
+    E,
+    /// GPIOF
+    F,
+    /// GPIOG
+    G,
+    /// GPIOH
+    H,
+}
+
+impl Port {
+    /// Returns pointer to the register block of the port.
+    ///
+    /// GPIO ports are laid out contiguously, 0x400 apart, starting from GPIOA (Reference Manual Ch. 2.2.2).
+    #[inline]
+    fn ptr(&self) -> *const stm32l4x6::gpioc::RegisterBlock {
+        (stm32l4x6::GPIOA::ptr() as usize + 0x400 * (*self as usize)) as *const _
+    }
+}
+
+/// Pin with both the port and the pin number erased from the type
+pub struct Pin<MODE> {
+    port: Port,
+    i: u8,
+    _mode: PhantomData<MODE>,
+}
+
+impl<MODE> Pin<MODE> {
+    /// Returns the port of the pin.
+    pub fn port(&self) -> Port {
+        self.port
+    }
+
+    /// Returns the number of the pin within its port.
+    pub fn index(&self) -> u8 {
+        self.i
+    }
+}
+
+impl<MODE> OutputPin for Pin<Output<MODE>> {
+    /// Sets high bit.
+    fn set_high(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.port.ptr()).bsrr.write(|w| w.bits(1 << self.i)) }
+    }
+
+    /// Sets low bit.
+    fn set_low(&mut self) {
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*self.port.ptr()).bsrr.write(|w| w.bits(1 << (16 + self.i))) }
+    }
+}
+
+impl<MODE> StatefulOutputPin for Pin<Output<MODE>> {
+    /// Returns whether high bit is set.
+    fn is_set_high(&self) -> bool {
+        !self.is_set_low()
+    }
+
+    /// Returns whether low bit is set.
+    fn is_set_low(&self) -> bool {
+        // NOTE(unsafe) atomic read with no side effects
+        unsafe { (*self.port.ptr()).odr.read().bits() & (1 << self.i) == 0 }
+    }
+}
+
+impl<MODE> InputPin for Pin<Input<MODE>> {
+    /// Returns whether input is high.
+    fn is_high(&self) -> bool {
+        !self.is_low()
+    }
+
+    /// Returns whether input is low.
+    fn is_low(&self) -> bool {
+        // NOTE(unsafe) atomic read with no side effects
+        unsafe { (*self.port.ptr()).idr.read().bits() & (1 << self.i) == 0 }
+    }
+}
+
+/// GPIO peripheral whose pins can be accessed together by `PortGroup`
+///
+/// Note: MUST not be implemented by user.
+pub trait GpioPort {
+    /// Returns pointer to the register block of the port.
+    fn ptr() -> *const stm32l4x6::gpioc::RegisterBlock;
+}
+
+/// Set of pins of port `GPIO` in mode `MODE`
+///
+/// Implemented for every pin, port-erased pin, tuples of up to 16 and arrays of up to 16 of them.
+/// Mask of concrete pins is known at compile time, while mask of erased pins is computed at runtime.
+///
+/// Note: MUST not be implemented by user.
+///
+/// # Safety
+///
+/// `mask` must only contain pins of port `GPIO` owned by the set, as the port group writes BSRR
+/// and reads IDR with it.
+pub unsafe trait PinSet<GPIO, MODE> {
+    /// Returns the mask of the pins, bit `n` standing for pin `n` of the port.
+    fn mask(&self) -> u16;
+}
+
+macro_rules! impl_pin_set_tuple {
+    ($($T:ident: $idx:tt),+) => {
+        unsafe impl<GPIO, MODE, $($T: PinSet<GPIO, MODE>),+> PinSet<GPIO, MODE> for ($($T,)+) {
+            #[inline]
+            fn mask(&self) -> u16 {
+                0 $(| self.$idx.mask())+
+            }
+        }
+    }
+}
+
+impl_pin_set_tuple!(A: 0);
+impl_pin_set_tuple!(A: 0, B: 1);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11, M: 12);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11, M: 12, N: 13);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11, M: 12, N: 13, O: 14);
+impl_pin_set_tuple!(A: 0, B: 1, C: 2, D: 3, E: 4, F: 5, G: 6, H: 7, I: 8, J: 9, K: 10, L: 11, M: 12, N: 13, O: 14, P: 15);
+
+macro_rules! impl_pin_set_array {
+    ($($N:expr),+) => {
+        $(
+            unsafe impl<GPIO, MODE, T: PinSet<GPIO, MODE>> PinSet<GPIO, MODE> for [T; $N] {
+                #[inline]
+                fn mask(&self) -> u16 {
+                    self.iter().fold(0, |mask, pin| mask | pin.mask())
+                }
+            }
+        )+
+    }
+}
+
+impl_pin_set_array!(1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16);
+
+/// Pins of one port that are written or read together with a single register access
+///
+/// ```rust
+/// let bus = (gpio_e.PE8, gpio_e.PE9, gpio_e.PE10, gpio_e.PE11, gpio_e.PE12, gpio_e.PE13, gpio_e.PE14, gpio_e.PE15);
+/// let mut bus = PortGroup::new(bus);
+/// // Changes all 8 pins on the same clock edge
+/// bus.write(u16::from(byte) << 8);
+/// ```
+pub struct PortGroup<GPIO, MODE, PINS> {
+    pins: PINS,
+    mask: u16,
+    _mode: PhantomData<(GPIO, MODE)>,
+}
+
+impl<GPIO: GpioPort, MODE, PINS: PinSet<GPIO, MODE>> PortGroup<GPIO, MODE, PINS> {
+    /// Creates new group out of `pins`.
+    pub fn new(pins: PINS) -> Self {
+        let mask = pins.mask();
+
+        PortGroup {
+            pins,
+            mask,
+            _mode: PhantomData,
+        }
+    }
+
+    /// Returns the mask of the group, bit `n` standing for pin `n` of the port.
+    pub fn mask(&self) -> u16 {
+        self.mask
+    }
+
+    /// Releases the pins.
+    pub fn free(self) -> PINS {
+        self.pins
+    }
+
+    /// Locks configuration of all pins of the group until the next reset.
+    ///
+    /// Fails, returning the group back, if the port has been locked before without these pins.
+    pub fn lock(self) -> Result<Locked<Self>, Self> {
+        match lock_pins::<GPIO>(self.mask) {
+            true => Ok(Locked(self)),
+            false => Err(self),
+        }
+    }
+}
+
+impl<GPIO: GpioPort, MODE, PINS> PortGroup<GPIO, Output<MODE>, PINS> {
+    /// Sets all pins of the group at once through BSRR.
+    ///
+    /// Bit `n` of `bits` is the level of pin `n` of the port; bits outside of the mask are ignored.
+    pub fn write(&mut self, bits: u16) {
+        let set = u32::from(bits & self.mask);
+        let reset = u32::from(!bits & self.mask);
+        // NOTE(unsafe) atomic write to a stateless register
+        unsafe { (*GPIO::ptr()).bsrr.write(|w| w.bits(set | (reset << 16))) }
+    }
+
+    /// Returns the levels the pins of the group are set to, bits outside of the mask are cleared.
+    pub fn output(&self) -> u16 {
+        // NOTE(unsafe) atomic read with no side effects
+        unsafe { (*GPIO::ptr()).odr.read().bits() as u16 & self.mask }
+    }
+}
+
+impl<GPIO: GpioPort, MODE, PINS> PortGroup<GPIO, Input<MODE>, PINS> {
+    /// Reads all pins of the group at once from IDR, bits outside of the mask are cleared.
+    pub fn read(&self) -> u16 {
+        // NOTE(unsafe) atomic read with no side effects
+        unsafe { (*GPIO::ptr()).idr.read().bits() as u16 & self.mask }
+    }
+}
+
+/// Sets the MODER field at `offset` to `mode`.
+///
+/// `Dynamic` pins switch their direction without `&mut MODER`, so every read-modify-write of MODER
+/// runs in a critical section, otherwise one of them could be lost if interrupted by the other.
+fn set_mode<GPIO: GpioPort>(offset: u32, mode: u32) {
+    let gpio = unsafe { &*GPIO::ptr() };
+    interrupt::free(|_| {
+        gpio.moder
+            .modify(|r, w| unsafe { w.bits((r.bits() & !(0b11 << offset)) | (mode << offset)) })
+    });
+}
+
+/// Performs the LCKR write sequence for the pins in `mask` (Reference Manual Ch. 8.4.8).
+///
+/// Once LCKK is set, LCKR is frozen until reset, so pins can only be locked once per port.
+/// Returns whether all pins in `mask` are locked afterwards.
+fn lock_pins<GPIO: GpioPort>(mask: u16) -> bool {
+    const LCKK: u32 = 1 << 16;
+
+    let gpio = unsafe { &*GPIO::ptr() };
+    interrupt::free(|_| {
+        // LCKR[15:0] must not change during the sequence
+        let pins = (gpio.lckr.read().bits() | u32::from(mask)) & 0xFFFF;
+        unsafe {
+            gpio.lckr.write(|w| w.bits(LCKK | pins));
+            gpio.lckr.write(|w| w.bits(pins));
+            gpio.lckr.write(|w| w.bits(LCKK | pins));
+        }
+        let _ = gpio.lckr.read();
+    });
+
+    let lckr = gpio.lckr.read().bits();
+    lckr & LCKK != 0 && lckr & u32::from(mask) == u32::from(mask)
+}
+
+/// Pin (or group of pins) whose configuration is locked until reset
+///
+/// Can still be read or written, but no longer converted into other modes.
+pub struct Locked<PIN>(PIN);
+
+impl<PIN: OutputPin> OutputPin for Locked<PIN> {
+    #[inline]
+    fn set_high(&mut self) {
+        self.0.set_high();
+    }
+    #[inline]
+    fn set_low(&mut self) {
+        self.0.set_low();
+    }
+}
+
+impl<PIN: StatefulOutputPin> StatefulOutputPin for Locked<PIN> {
+    #[inline]
+    fn is_set_high(&self) -> bool {
+        self.0.is_set_high()
+    }
+    #[inline]
+    fn is_set_low(&self) -> bool {
+        self.0.is_set_low()
+    }
+}
+
+impl<PIN: OutputPin + StatefulOutputPin> toggleable::Default for Locked<PIN> {}
+
+impl<PIN: InputPin> InputPin for Locked<PIN> {
+    #[inline]
+    fn is_high(&self) -> bool {
+        self.0.is_high()
+    }
+    #[inline]
+    fn is_low(&self) -> bool {
+        self.0.is_low()
+    }
+}
+
+impl<GPIO: GpioPort, MODE, PINS> Locked<PortGroup<GPIO, Output<MODE>, PINS>> {
+    /// Sets all pins of the group at once, see `PortGroup::write`.
+    #[inline]
+    pub fn write(&mut self, bits: u16) {
+        self.0.write(bits)
+    }
+
+    /// Returns the levels the pins of the group are set to, see `PortGroup::output`.
+    #[inline]
+    pub fn output(&self) -> u16 {
+        self.0.output()
+    }
+}
+
+impl<GPIO: GpioPort, MODE, PINS> Locked<PortGroup<GPIO, Input<MODE>, PINS>> {
+    /// Reads all pins of the group at once, see `PortGroup::read`.
+    #[inline]
+    pub fn read(&self) -> u16 {
+        self.0.read()
+    }
+}
+
 /// Generic LED
-pub struct Led<PIN>(PIN);
+pub struct Led<PIN>(pub(crate) PIN);
 impl<PIN: OutputPin + StatefulOutputPin> Led<PIN> {
     #[inline]
     /// Turns LED off.
@@ -461,20 +1057,27 @@ impl_parts!(
 // GPIOx_AFRL (for pin 0 to 7) and GPIOx_AFRH (for pin 8 to 15) registers
 //
 // The GPIO ports (and pins) enumerated here are exposed on all package variants of the STM32L4x6.
-// Larger chips have more pins, and so have additional definitions in their respective modules.
-impl_gpio!(A, GPIOA, gpioaen, gpioarst,
+// Larger packages have more pins, and so have additional definitions in `ports`.
+impl_gpio!(A, GPIOA, gpioaen, gpioarst, PAx,
            AFRL: [PA0, 0; PA1, 1; PA2, 2; PA3, 3; PA4, 4; PA5, 5; PA6, 6; PA7, 7;],
            AFRH: [PA8, 8; PA9, 9; PA10, 10; PA11, 11; PA12, 12; PA13, 13; PA14, 14; PA15, 15; ]
           );
-impl_gpio!(B, GPIOB, gpioben, gpiobrst,
+impl_gpio!(B, GPIOB, gpioben, gpiobrst, PBx,
            AFRL: [PB0, 0; PB1, 1; PB2, 2; PB3, 3; PB4, 4; PB5, 5; PB6, 6; PB7, 7;],
            AFRH: [PB8, 8; PB9, 9; PB10, 10; PB11, 11; PB12, 12; PB13, 13; PB14, 14; PB15, 15; ]
           );
-impl_gpio!(C, GPIOC, gpiocen, gpiocrst,
+impl_gpio!(C, GPIOC, gpiocen, gpiocrst, PCx,
            AFRL: [PC0, 0; PC1, 1; PC2, 2; PC3, 3; PC4, 4; PC5, 5; PC6, 6; PC7, 7;],
            AFRH: [PC8, 8; PC9, 9; PC10, 10; PC11, 11; PC12, 12; PC13, 13; PC14, 14; PC15, 15; ]
           );
 
+pub mod af;
+
+#[cfg(any(feature = "LQFP64", feature = "LQFP100", feature = "LQFP144", feature = "UFBGA169"))]
+pub mod ports;
+#[cfg(any(feature = "LQFP64", feature = "LQFP100", feature = "LQFP144", feature = "UFBGA169"))]
+pub use self::ports::*;
+
 #[cfg(feature = "STM32L476VG")]
 pub mod stm32l476vg;
 
@@ -0,0 +1,75 @@
+//! GPIO ports D to H
+//!
+//! Which of their pins are bonded out depends on the package, selected by the chip feature:
+//!
+//! | Package  | GPIOD   | GPIOE | GPIOF | GPIOG | GPIOH          |
+//! |----------|---------|-------|-------|-------|----------------|
+//! | LQFP64   | PD2     |       |       |       | PH0-1          |
+//! | LQFP100  | all     | all   |       |       | PH0-1          |
+//! | LQFP144  | all     | all   | all   | all   | PH0-1          |
+//! | UFBGA169 | all     | all   | all   | all   | all except PH3 |
+//!
+//! PH3 of the STM32L496xx/4A6xx is shared with BOOT0 and has no alternate functions, so it is not
+//! described here.
+//!
+//! Out of scope for now:
+//!
+//! - port I (PI0-11) of the UFBGA169 package, as the `stm32l4x6` crate, generated from the SVD of
+//!   the STM32L476, has no `GPIOI`;
+//! - the UFBGA132 package (STM32L476QG/486QG/496QG/4A6QG), which has no chip feature, as only
+//!   part of its ports F and G is bonded out.
+
+use stm32l4x6;
+
+use super::*;
+
+#[cfg(any(feature = "LQFP64", feature = "LQFP100", feature = "LQFP144", feature = "UFBGA169"))]
+impl_parts!(
+    GPIOD, gpioc;
+    GPIOH, gpioc;
+    );
+#[cfg(any(feature = "LQFP100", feature = "LQFP144", feature = "UFBGA169"))]
+impl_parts!(
+    GPIOE, gpioc;
+    );
+#[cfg(any(feature = "LQFP144", feature = "UFBGA169"))]
+impl_parts!(
+    GPIOF, gpioc;
+    GPIOG, gpioc;
+    );
+
+#[cfg(feature = "LQFP64")]
+impl_gpio!(D, GPIOD, gpioden, gpiodrst, PDx,
+           AFRL: [PD2, 2;],
+           AFRH: []
+);
+#[cfg(any(feature = "LQFP100", feature = "LQFP144", feature = "UFBGA169"))]
+impl_gpio!(D, GPIOD, gpioden, gpiodrst, PDx,
+           AFRL: [PD0, 0; PD1, 1; PD2, 2; PD3, 3; PD4, 4; PD5, 5; PD6, 6; PD7, 7;],
+           AFRH: [PD8, 8; PD9, 9; PD10, 10; PD11, 11; PD12, 12; PD13, 13; PD14, 14; PD15, 15; ]
+);
+#[cfg(any(feature = "LQFP100", feature = "LQFP144", feature = "UFBGA169"))]
+impl_gpio!(E, GPIOE, gpioeen, gpioerst, PEx,
+           AFRL: [PE0, 0; PE1, 1; PE2, 2; PE3, 3; PE4, 4; PE5, 5; PE6, 6; PE7, 7;],
+           AFRH: [PE8, 8; PE9, 9; PE10, 10; PE11, 11; PE12, 12; PE13, 13; PE14, 14; PE15, 15; ]
+);
+#[cfg(any(feature = "LQFP144", feature = "UFBGA169"))]
+impl_gpio!(F, GPIOF, gpiofen, gpiofrst, PFx,
+           AFRL: [PF0, 0; PF1, 1; PF2, 2; PF3, 3; PF4, 4; PF5, 5; PF6, 6; PF7, 7;],
+           AFRH: [PF8, 8; PF9, 9; PF10, 10; PF11, 11; PF12, 12; PF13, 13; PF14, 14; PF15, 15; ]
+);
+#[cfg(any(feature = "LQFP144", feature = "UFBGA169"))]
+impl_gpio!(G, GPIOG, gpiogen, gpiogrst, PGx,
+           AFRL: [PG0, 0; PG1, 1; PG2, 2; PG3, 3; PG4, 4; PG5, 5; PG6, 6; PG7, 7;],
+           AFRH: [PG8, 8; PG9, 9; PG10, 10; PG11, 11; PG12, 12; PG13, 13; PG14, 14; PG15, 15; ]
+);
+#[cfg(any(feature = "LQFP64", feature = "LQFP100", feature = "LQFP144"))]
+impl_gpio!(H, GPIOH, gpiohen, gpiohrst, PHx,
+           AFRL: [PH0, 0; PH1, 1;],
+           AFRH: []
+);
+#[cfg(feature = "UFBGA169")]
+impl_gpio!(H, GPIOH, gpiohen, gpiohrst, PHx,
+           AFRL: [PH0, 0; PH1, 1; PH2, 2; PH4, 4; PH5, 5; PH6, 6; PH7, 7;],
+           AFRH: [PH8, 8; PH9, 9; PH10, 10; PH11, 11; PH12, 12; PH13, 13; PH14, 14; PH15, 15; ]
+);
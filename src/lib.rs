@@ -11,15 +11,19 @@
 //! instructions and add this crate as a dependency in step number 5 and make sure you enable the
 //! "rt" Cargo feature of this crate.
 //!
+//! Also enable the feature of exactly one chip (e.g. "STM32L476VG" or "STM32L4A6ZG"), which
+//! selects the GPIO lines of its package, or the feature of a board (e.g. "NUCLEO-L476RG"), which
+//! enables the one of the chip mounted on it.
+//!
 //! [cortex-m-quickstart]: https://docs.rs/cortex-m-quickstart/~0.2.3
 
-#![feature(never_type)]
 #![feature(unsize)]
 #![no_std]
 
 extern crate cast;
 extern crate cortex_m;
 extern crate embedded_hal as hal;
+#[macro_use]
 extern crate nb;
 pub extern crate stm32l4x6;
 
@@ -28,12 +32,31 @@ use core::marker;
 use core::mem;
 use core::ops;
 
+// Two chips differ by their product line or their package
+#[cfg(any(
+    all(feature = "STM32L476", any(feature = "STM32L486", feature = "STM32L496", feature = "STM32L4A6")),
+    all(feature = "STM32L486", any(feature = "STM32L496", feature = "STM32L4A6")),
+    all(feature = "STM32L496", feature = "STM32L4A6")
+))]
+compile_error!("Select the feature of exactly one chip, e.g. STM32L476VG");
+#[cfg(any(
+    all(feature = "LQFP64", any(feature = "LQFP100", feature = "LQFP144", feature = "UFBGA169")),
+    all(feature = "LQFP100", any(feature = "LQFP144", feature = "UFBGA169")),
+    all(feature = "LQFP144", feature = "UFBGA169")
+))]
+compile_error!("Select the feature of exactly one chip, e.g. STM32L476VG");
+
+// Declared first so that its helper macros (e.g. `define_led!`) are visible to board modules
+#[macro_use]
+pub mod gpio;
+
+pub mod board;
 pub mod common;
 pub mod config;
 pub mod delay;
 pub mod dma;
+pub mod exti;
 pub mod flash;
-pub mod gpio;
 pub mod lcd;
 pub mod power;
 pub mod rcc;
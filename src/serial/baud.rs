@@ -0,0 +1,259 @@
+//! Baud rate generation
+//!
+//! See Reference Manual Ch. 40.5.4 (USART) and Ch. 41.4.4 (LPUART)
+
+use time::Bps;
+
+use super::config::Oversampling;
+
+/// Baud rate error
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BaudRateError {
+    /// The kernel clock is too slow for the requested baud rate
+    TooHigh,
+    /// The kernel clock is too fast for the requested baud rate
+    TooLow,
+    /// The closest achievable baud rate is out of tolerance
+    Inaccurate(Baud),
+    /// The peripheral or its mode doesn't support the configuration, e.g. 1.5 stop bits on LPUART1
+    /// or more than 115200 bps in IrDA mode
+    Unsupported,
+    #[doc(hidden)] _Extensible,
+}
+
+/// Computed baud rate
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Baud {
+    /// Value of the BRR register
+    pub brr: u32,
+    /// Requested baud rate
+    pub requested: Bps,
+    /// Achieved baud rate, rounded to the closest integer
+    pub actual: Bps,
+}
+
+#[inline]
+fn div_round(num: u64, den: u64) -> u64 {
+    (num + den / 2) / den
+}
+
+impl Baud {
+    /// Computes BRR of a USART clocked at `clk_f`
+    ///
+    /// USARTDIV is rounded to the closest integer and must be within 16 and 0xFFFF. With an
+    /// oversampling of 8, BRR[2:0] holds USARTDIV[3:0] shifted right by one and BRR[3] is cleared,
+    /// so USARTDIV is rounded to the closest even integer.
+    pub fn usart(clk_f: u32, baud_rate: Bps, oversampling: Oversampling) -> Result<Self, BaudRateError> {
+        if baud_rate.0 == 0 {
+            return Err(BaudRateError::TooLow);
+        }
+
+        let (clk_f, usartdiv) = match oversampling {
+            Oversampling::Over16 => (clk_f as u64, div_round(clk_f as u64, baud_rate.0 as u64)),
+            Oversampling::Over8 => (2 * clk_f as u64, 2 * div_round(clk_f as u64, baud_rate.0 as u64)),
+        };
+        if usartdiv < 16 {
+            return Err(BaudRateError::TooHigh);
+        } else if usartdiv > 0xFFFF {
+            return Err(BaudRateError::TooLow);
+        }
+
+        let brr = match oversampling {
+            Oversampling::Over16 => usartdiv,
+            Oversampling::Over8 => (usartdiv & !0xF) | ((usartdiv & 0xF) >> 1),
+        };
+
+        Ok(Baud {
+            brr: brr as u32,
+            requested: baud_rate,
+            actual: Bps(div_round(clk_f, usartdiv) as u32),
+        })
+    }
+
+    /// Computes BRR of a LPUART clocked at `clk_f`
+    ///
+    /// BRR is `256 * clk_f / baud_rate`, rounded to the closest integer, and must be within 0x300
+    /// and 0xFFFFF.
+    pub fn lpuart(clk_f: u32, baud_rate: Bps) -> Result<Self, BaudRateError> {
+        if baud_rate.0 == 0 {
+            return Err(BaudRateError::TooLow);
+        }
+
+        let clk_f = 256 * clk_f as u64;
+        let brr = div_round(clk_f, baud_rate.0 as u64);
+        if brr < 0x300 {
+            return Err(BaudRateError::TooHigh);
+        } else if brr > 0xFFFFF {
+            return Err(BaudRateError::TooLow);
+        }
+
+        Ok(Baud {
+            brr: brr as u32,
+            requested: baud_rate,
+            actual: Bps(div_round(clk_f, brr) as u32),
+        })
+    }
+
+    /// Computes the baud rate of BRR, e.g. after automatic baud rate detection
+    pub fn from_brr(clk_f: u32, brr: u32, oversampling: Oversampling) -> Bps {
+        let (clk_f, usartdiv) = match oversampling {
+            Oversampling::Over16 => (clk_f as u64, brr as u64),
+            Oversampling::Over8 => (2 * clk_f as u64, (brr as u64 & !0xF) | ((brr as u64 & 0x7) << 1)),
+        };
+        match usartdiv {
+            0 => Bps(0),
+            _ => Bps(div_round(clk_f, usartdiv) as u32),
+        }
+    }
+
+    /// Returns the deviation of the achieved baud rate in parts per million
+    pub fn error_ppm(&self) -> i32 {
+        let diff = self.actual.0 as i64 - self.requested.0 as i64;
+        (diff * 1_000_000 / self.requested.0 as i64) as i32
+    }
+
+    /// Returns the deviation of the achieved baud rate in percent
+    pub fn error_percent(&self) -> f32 {
+        (self.actual.0 as f32 - self.requested.0 as f32) * 100.0 / self.requested.0 as f32
+    }
+
+    /// Rejects the baud rate if it deviates more than `tolerance_ppm` from the requested one
+    pub fn within(self, tolerance_ppm: u32) -> Result<Self, BaudRateError> {
+        match self.error_ppm().unsigned_abs() <= tolerance_ppm {
+            true => Ok(self),
+            false => Err(BaudRateError::Inaccurate(self)),
+        }
+    }
+}
+
+/// Computes the prescaler dividing `clk_f` down to the 1.8432 MHz low-power IrDA clock
+///
+/// The divided clock must be within 1.42 and 2.12 MHz, `TooHigh` is returned if the kernel clock
+/// is too slow and `TooLow` if it is too fast.
+pub fn irda_low_power_prescaler(clk_f: u32) -> Result<u8, BaudRateError> {
+    let psc = div_round(clk_f as u64, 1_843_200);
+    if psc == 0 || clk_f as u64 / psc < 1_420_000 {
+        return Err(BaudRateError::TooHigh);
+    } else if psc > 0xFF || clk_f as u64 / psc > 2_120_000 {
+        return Err(BaudRateError::TooLow);
+    }
+
+    Ok(psc as u8)
+}
+
+/// Maximum baud rate of IrDA SIR
+pub const IRDA_MAX_BAUD_RATE: Bps = Bps(115_200);
+
+/// Rejects a BRR of a USART clocked at `clk_f` that gives a baud rate above `IRDA_MAX_BAUD_RATE`
+///
+/// The baud rate is compared through USARTDIV, so that 115200 bps is accepted whatever the
+/// rounding of the achieved baud rate.
+pub fn irda_check_brr(clk_f: u32, brr: u32, oversampling: Oversampling) -> Result<(), BaudRateError> {
+    match Baud::usart(clk_f, IRDA_MAX_BAUD_RATE, oversampling) {
+        Ok(ref max) if brr < max.brr => Err(BaudRateError::Unsupported),
+        // Otherwise the kernel clock is too slow for 115200 bps, or too fast for any BRR
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{irda_check_brr, irda_low_power_prescaler, Baud, BaudRateError};
+    use serial::config::Oversampling;
+    use time::Bps;
+
+    #[test]
+    fn usart_over16() {
+        let baud = Baud::usart(80_000_000, Bps(115_200), Oversampling::Over16).unwrap();
+        assert_eq!(baud.brr, 694);
+        assert_eq!(baud.actual, Bps(115_274));
+        assert_eq!(baud.error_ppm(), 642);
+
+        // Truncation would give 34, i.e. 117647 bps
+        let baud = Baud::usart(4_000_000, Bps(115_200), Oversampling::Over16).unwrap();
+        assert_eq!(baud.brr, 35);
+        assert_eq!(baud.actual, Bps(114_286));
+        assert_eq!(baud.error_ppm(), -7_934);
+        assert!((baud.error_percent() + 0.7934).abs() < 0.001);
+
+        let baud = Baud::usart(16_000_000, Bps(1_000_000), Oversampling::Over16).unwrap();
+        assert_eq!(baud.brr, 16);
+        assert_eq!(baud.error_ppm(), 0);
+    }
+
+    #[test]
+    fn usart_over8() {
+        // USARTDIV = 1388 = 0x56C, as bit 0 can't be set
+        let baud = Baud::usart(80_000_000, Bps(115_200), Oversampling::Over8).unwrap();
+        assert_eq!(baud.brr, 0x566);
+        assert_eq!(baud.actual, Bps(115_274));
+        assert_eq!(baud.error_ppm(), 642);
+
+        let baud = Baud::usart(80_000_000, Bps(1_000_000), Oversampling::Over8).unwrap();
+        assert_eq!(baud.brr, 0xA0);
+        assert_eq!(baud.actual, Bps(1_000_000));
+
+        // Twice the maximum baud rate of oversampling by 16
+        let baud = Baud::usart(16_000_000, Bps(2_000_000), Oversampling::Over8).unwrap();
+        assert_eq!(baud.brr, 0x10);
+        assert_eq!(baud.actual, Bps(2_000_000));
+    }
+
+    #[test]
+    fn from_brr() {
+        assert_eq!(Baud::from_brr(80_000_000, 694, Oversampling::Over16), Bps(115_274));
+        assert_eq!(Baud::from_brr(80_000_000, 0x566, Oversampling::Over8), Bps(115_274));
+        assert_eq!(Baud::from_brr(16_000_000, 0x10, Oversampling::Over8), Bps(2_000_000));
+        assert_eq!(Baud::from_brr(16_000_000, 0, Oversampling::Over16), Bps(0));
+    }
+
+    #[test]
+    fn usart_out_of_range() {
+        assert_eq!(Baud::usart(1_000_000, Bps(115_200), Oversampling::Over16), Err(BaudRateError::TooHigh));
+        assert!(Baud::usart(1_000_000, Bps(115_200), Oversampling::Over8).is_ok());
+        assert_eq!(Baud::usart(80_000_000, Bps(300), Oversampling::Over16), Err(BaudRateError::TooLow));
+        assert_eq!(Baud::usart(80_000_000, Bps(0), Oversampling::Over16), Err(BaudRateError::TooLow));
+    }
+
+    #[test]
+    fn lpuart() {
+        let baud = Baud::lpuart(32_768, Bps(9_600)).unwrap();
+        assert_eq!(baud.brr, 874);
+        assert_eq!(baud.actual, Bps(9_598));
+
+        let baud = Baud::lpuart(80_000_000, Bps(115_200)).unwrap();
+        assert_eq!(baud.brr, 177_778);
+        assert_eq!(baud.actual, Bps(115_200));
+
+        assert_eq!(Baud::lpuart(32_768, Bps(19_200)), Err(BaudRateError::TooHigh));
+        assert_eq!(Baud::lpuart(80_000_000, Bps(9_600)), Err(BaudRateError::TooLow));
+    }
+
+    #[test]
+    fn tolerance() {
+        let baud = Baud::usart(4_000_000, Bps(115_200), Oversampling::Over16).unwrap();
+        assert_eq!(baud.within(10_000), Ok(baud));
+        assert_eq!(baud.within(5_000), Err(BaudRateError::Inaccurate(baud)));
+    }
+
+    #[test]
+    fn irda_prescaler() {
+        assert_eq!(irda_low_power_prescaler(80_000_000), Ok(43));
+        assert_eq!(irda_low_power_prescaler(16_000_000), Ok(9));
+        assert_eq!(irda_low_power_prescaler(2_000_000), Ok(1));
+        assert_eq!(irda_low_power_prescaler(32_768), Err(BaudRateError::TooHigh));
+        assert_eq!(irda_low_power_prescaler(1_000_000), Err(BaudRateError::TooHigh));
+    }
+
+    #[test]
+    fn irda_max_baud_rate() {
+        // 115274 bps
+        assert_eq!(irda_check_brr(80_000_000, 694, Oversampling::Over16), Ok(()));
+        // 115440 bps
+        assert_eq!(irda_check_brr(80_000_000, 693, Oversampling::Over16), Err(BaudRateError::Unsupported));
+        assert_eq!(irda_check_brr(80_000_000, 0x566, Oversampling::Over8), Ok(()));
+        assert_eq!(irda_check_brr(80_000_000, 0x565, Oversampling::Over8), Err(BaudRateError::Unsupported));
+        // The highest baud rate of a 1 MHz kernel clock is 62500 bps
+        assert_eq!(irda_check_brr(1_000_000, 16, Oversampling::Over16), Ok(()));
+    }
+}
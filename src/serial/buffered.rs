@@ -0,0 +1,526 @@
+//! Interrupt-driven serial with ring buffers
+//!
+//! `Buffered` owns a `Serial` and two statically allocated queues. Its `on_interrupt` method is
+//! called from the USART interrupt handler: it moves received words into the receive queue and
+//! feeds the transmitter from the transmit queue. The application reads and writes the queues
+//! without blocking. They aren't lock-free though: the application must access them in a critical
+//! section, typically through a `cortex_m::interrupt::Mutex` shared with the handler.
+//!
+//! ```rust
+//! static SERIAL: Mutex<RefCell<Option<Buffered<USART2, (PA2<AF7>, PA3<AF7>), [u8; 64], [u8; 256]>>>> =
+//!     Mutex::new(RefCell::new(None));
+//!
+//! fn usart2() {
+//!     interrupt::free(|cs| SERIAL.borrow(cs).borrow_mut().as_mut().map(|serial| serial.on_interrupt()));
+//! }
+//! ```
+
+use core::fmt;
+use core::marker::{PhantomData, Unsize};
+
+use hal::serial;
+use nb;
+use stm32l4x6::{LPUART1, USART1, USART2, USART3, UART4, UART5};
+
+use super::{Error, Rx, Serial, Tx};
+
+/// Fixed-capacity FIFO of bytes in a statically allocated buffer
+///
+/// The queue isn't synchronized, a queue shared with an interrupt handler must only be accessed in
+/// a critical section.
+pub struct Queue<B: 'static> {
+    buffer: &'static mut B,
+    read: usize,
+    len: usize,
+}
+
+impl<B> Queue<B>
+where
+    B: Unsize<[u8]>,
+{
+    /// Creates an empty queue with the capacity of `buffer`
+    pub fn new(buffer: &'static mut B) -> Self {
+        Queue { buffer, read: 0, len: 0 }
+    }
+
+    /// Returns the maximum number of bytes in the queue
+    pub fn capacity(&self) -> usize {
+        let buffer: &[u8] = self.buffer;
+        buffer.len()
+    }
+
+    /// Returns the number of bytes in the queue
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns whether the queue is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns whether the queue is full
+    pub fn is_full(&self) -> bool {
+        self.len == self.capacity()
+    }
+
+    /// Appends a byte, or gives it back if the queue is full
+    pub fn enqueue(&mut self, byte: u8) -> Result<(), u8> {
+        if self.is_full() {
+            return Err(byte);
+        }
+
+        let capacity = self.capacity();
+        let buffer: &mut [u8] = self.buffer;
+        buffer[(self.read + self.len) % capacity] = byte;
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Returns the oldest byte without removing it
+    pub fn peek(&self) -> Option<u8> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let buffer: &[u8] = self.buffer;
+        Some(buffer[self.read])
+    }
+
+    /// Removes the oldest byte
+    pub fn dequeue(&mut self) -> Option<u8> {
+        if self.is_empty() {
+            return None;
+        }
+
+        let capacity = self.capacity();
+        let buffer: &[u8] = self.buffer;
+        let byte = buffer[self.read];
+        self.read = (self.read + 1) % capacity;
+        self.len -= 1;
+        Some(byte)
+    }
+}
+
+/// Interrupt-driven serial
+///
+/// Only the lower 8 bits of the words are buffered.
+pub struct Buffered<USART, PINS, RXB: 'static, TXB: 'static> {
+    serial: Serial<USART, PINS>,
+    buffers: Buffers<RXB, TXB>,
+}
+
+impl<USART, PINS, RXB, TXB> Buffered<USART, PINS, RXB, TXB> {
+    /// Returns the number of received bytes dropped because the receive queue was full
+    pub fn rx_overflows(&self) -> u32 {
+        self.buffers.rx_overflows
+    }
+
+    /// Returns the number of bytes dropped by `fmt::Write` because the transmit queue was full
+    pub fn tx_overflows(&self) -> u32 {
+        self.buffers.tx_overflows
+    }
+}
+
+// Transmitter and receiver of a `Buffered`, mocked by the tests
+trait Usart {
+    fn read(&mut self) -> nb::Result<u8, Error>;
+    fn write(&mut self, byte: u8) -> nb::Result<(), Error>;
+    fn flush(&mut self) -> nb::Result<(), Error>;
+    fn listen_txe(&mut self, listen: bool);
+    fn is_listening_txe(&self) -> bool;
+}
+
+// Queues and pending errors of a `Buffered`
+struct Buffers<RXB: 'static, TXB: 'static> {
+    rx: Queue<RXB>,
+    tx: Queue<TXB>,
+    error: Option<Error>,
+    tx_error: Option<Error>,
+    rx_overflows: u32,
+    tx_overflows: u32,
+}
+
+impl<RXB, TXB> Buffers<RXB, TXB>
+where
+    RXB: Unsize<[u8]>,
+    TXB: Unsize<[u8]>,
+{
+    fn new(rx_buffer: &'static mut RXB, tx_buffer: &'static mut TXB) -> Self {
+        Buffers {
+            rx: Queue::new(rx_buffer),
+            tx: Queue::new(tx_buffer),
+            error: None,
+            tx_error: None,
+            rx_overflows: 0,
+            tx_overflows: 0,
+        }
+    }
+
+    fn on_interrupt<U: Usart>(&mut self, usart: &mut U) {
+        loop {
+            match usart.read() {
+                Ok(byte) => if self.rx.enqueue(byte).is_err() {
+                    self.rx_overflows = self.rx_overflows.wrapping_add(1);
+                },
+                Err(nb::Error::Other(error)) => self.error = Some(error),
+                Err(nb::Error::WouldBlock) => break,
+            }
+        }
+
+        if usart.is_listening_txe() {
+            while let Some(byte) = self.tx.peek() {
+                // Stops if the transmitter is busy or reports an error (e.g. CTS was deasserted),
+                // the byte is retried on the next interrupt
+                match usart.write(byte) {
+                    Ok(()) => self.tx.dequeue(),
+                    Err(nb::Error::Other(error)) => {
+                        self.tx_error = Some(error);
+                        break;
+                    }
+                    Err(nb::Error::WouldBlock) => break,
+                };
+            }
+            if self.tx.is_empty() {
+                usart.listen_txe(false);
+            }
+        }
+    }
+
+    fn read(&mut self) -> nb::Result<u8, Error> {
+        if let Some(error) = self.error.take() {
+            return Err(nb::Error::Other(error));
+        }
+        self.rx.dequeue().ok_or(nb::Error::WouldBlock)
+    }
+
+    fn flush<U: Usart>(&mut self, usart: &mut U) -> nb::Result<(), Error> {
+        if let Some(error) = self.tx_error.take() {
+            return Err(nb::Error::Other(error));
+        }
+        if !self.tx.is_empty() {
+            return Err(nb::Error::WouldBlock);
+        }
+        usart.flush()
+    }
+
+    fn write<U: Usart>(&mut self, usart: &mut U, byte: u8) -> nb::Result<(), Error> {
+        if let Some(error) = self.tx_error.take() {
+            return Err(nb::Error::Other(error));
+        }
+        self.tx.enqueue(byte).map_err(|_| nb::Error::WouldBlock)?;
+        usart.listen_txe(true);
+        Ok(())
+    }
+
+    fn write_str<U: Usart>(&mut self, usart: &mut U, s: &str) -> fmt::Result {
+        let mut result = Ok(());
+        for &byte in s.as_bytes() {
+            if self.tx.enqueue(byte).is_err() {
+                self.tx_overflows = self.tx_overflows.wrapping_add(1);
+                result = Err(fmt::Error);
+            }
+        }
+        usart.listen_txe(true);
+        result
+    }
+}
+
+macro_rules! buffered {
+    ($($USARTX:ident,)+) => {
+        $(
+            impl<PINS> Usart for Serial<$USARTX, PINS> {
+                fn read(&mut self) -> nb::Result<u8, Error> {
+                    Rx::<$USARTX>::read_word(&mut self.errors).map(|word| word as u8)
+                }
+
+                fn write(&mut self, byte: u8) -> nb::Result<(), Error> {
+                    let mut tx: Tx<$USARTX> = Tx { _usart: PhantomData };
+                    serial::Write::<u8>::write(&mut tx, byte)
+                }
+
+                fn flush(&mut self) -> nb::Result<(), Error> {
+                    let mut tx: Tx<$USARTX> = Tx { _usart: PhantomData };
+                    serial::Write::<u8>::flush(&mut tx)
+                }
+
+                fn listen_txe(&mut self, listen: bool) {
+                    self.usart.cr1.modify(|_,w| w.txeie().bit(listen));
+                }
+
+                fn is_listening_txe(&self) -> bool {
+                    self.usart.cr1.read().txeie().bit_is_set()
+                }
+            }
+
+            impl<PINS, RXB, TXB> Buffered<$USARTX, PINS, RXB, TXB>
+            where
+                RXB: Unsize<[u8]>,
+                TXB: Unsize<[u8]>,
+            {
+                /// Starts interrupt-driven reception
+                ///
+                /// The USART interrupt must be unmasked in the NVIC and call `on_interrupt`.
+                pub fn new(
+                    serial: Serial<$USARTX, PINS>,
+                    rx_buffer: &'static mut RXB,
+                    tx_buffer: &'static mut TXB,
+                ) -> Self {
+                    // RXNEIE also enables the overrun interrupt
+                    serial.usart.cr1.modify(|_,w| w.rxneie().set_bit());
+
+                    Buffered {
+                        serial,
+                        buffers: Buffers::new(rx_buffer, tx_buffer),
+                    }
+                }
+
+                /// Handles the USART interrupt
+                pub fn on_interrupt(&mut self) {
+                    self.buffers.on_interrupt(&mut self.serial)
+                }
+
+                /// Stops interrupt-driven operation, discards the queued bytes and releases the
+                /// `Serial` and the buffers
+                pub fn free(self) -> (Serial<$USARTX, PINS>, &'static mut RXB, &'static mut TXB) {
+                    self.serial.usart.cr1.modify(|_,w| w.rxneie().clear_bit().txeie().clear_bit());
+                    (self.serial, self.buffers.rx.buffer, self.buffers.tx.buffer)
+                }
+            }
+
+            impl<PINS, RXB, TXB> serial::Read<u8> for Buffered<$USARTX, PINS, RXB, TXB>
+            where
+                RXB: Unsize<[u8]>,
+                TXB: Unsize<[u8]>,
+            {
+                type Error = Error;
+
+                /// Returns the oldest received byte
+                ///
+                /// The last reception error is reported once, before the queued bytes.
+                fn read(&mut self) -> nb::Result<u8, Error> {
+                    self.buffers.read()
+                }
+            }
+
+            impl<PINS, RXB, TXB> serial::Write<u8> for Buffered<$USARTX, PINS, RXB, TXB>
+            where
+                RXB: Unsize<[u8]>,
+                TXB: Unsize<[u8]>,
+            {
+                type Error = Error;
+
+                /// Waits until the transmit queue is empty and the last byte has been sent
+                ///
+                /// The last transmission error is reported once.
+                fn flush(&mut self) -> nb::Result<(), Error> {
+                    self.buffers.flush(&mut self.serial)
+                }
+
+                /// Queues a byte, blocks only if the transmit queue is full
+                ///
+                /// The last transmission error is reported once, without queuing the byte.
+                fn write(&mut self, byte: u8) -> nb::Result<(), Error> {
+                    self.buffers.write(&mut self.serial, byte)
+                }
+            }
+
+            impl<PINS, RXB, TXB> fmt::Write for Buffered<$USARTX, PINS, RXB, TXB>
+            where
+                RXB: Unsize<[u8]>,
+                TXB: Unsize<[u8]>,
+            {
+                /// Queues the bytes of `s`
+                ///
+                /// Doesn't wait for room in the transmit queue, as it may be called with the
+                /// interrupt disabled; the bytes that don't fit are dropped and counted, and
+                /// `fmt::Error` is returned.
+                fn write_str(&mut self, s: &str) -> fmt::Result {
+                    self.buffers.write_str(&mut self.serial, s)
+                }
+            }
+        )+
+    }
+}
+
+buffered! {
+    USART1,
+    USART2,
+    USART3,
+    UART4,
+    UART5,
+    LPUART1,
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use nb;
+
+    use super::{Buffers, Queue, Usart};
+    use serial::Error;
+
+    // Receives `rx`, after `rx_error` if any, and transmits `tx_room` bytes into `sent` before
+    // reporting `tx_error` if any, then `WouldBlock`
+    struct Mock {
+        rx: &'static [u8],
+        rx_error: Option<Error>,
+        tx_room: usize,
+        tx_error: Option<Error>,
+        sent: [u8; 8],
+        sent_len: usize,
+        txe: bool,
+    }
+
+    impl Mock {
+        fn new(rx: &'static [u8], tx_room: usize) -> Self {
+            Mock { rx, rx_error: None, tx_room, tx_error: None, sent: [0; 8], sent_len: 0, txe: false }
+        }
+
+        fn sent(&self) -> &[u8] {
+            &self.sent[..self.sent_len]
+        }
+    }
+
+    impl Usart for Mock {
+        fn read(&mut self) -> nb::Result<u8, Error> {
+            if let Some(error) = self.rx_error.take() {
+                return Err(nb::Error::Other(error));
+            }
+            match self.rx.split_first() {
+                Some((&byte, rest)) => {
+                    self.rx = rest;
+                    Ok(byte)
+                }
+                None => Err(nb::Error::WouldBlock),
+            }
+        }
+
+        fn write(&mut self, byte: u8) -> nb::Result<(), Error> {
+            if self.tx_room == 0 {
+                return match self.tx_error.take() {
+                    Some(error) => Err(nb::Error::Other(error)),
+                    None => Err(nb::Error::WouldBlock),
+                };
+            }
+            self.tx_room -= 1;
+            self.sent[self.sent_len] = byte;
+            self.sent_len += 1;
+            Ok(())
+        }
+
+        fn flush(&mut self) -> nb::Result<(), Error> {
+            Ok(())
+        }
+
+        fn listen_txe(&mut self, listen: bool) {
+            self.txe = listen;
+        }
+
+        fn is_listening_txe(&self) -> bool {
+            self.txe
+        }
+    }
+
+    #[test]
+    fn queue_wraps_around() {
+        static mut BUFFER: [u8; 3] = [0; 3];
+        let mut queue = Queue::new(unsafe { &mut BUFFER });
+
+        assert_eq!(queue.capacity(), 3);
+        assert_eq!(queue.dequeue(), None);
+        assert_eq!(queue.enqueue(1), Ok(()));
+        assert_eq!(queue.enqueue(2), Ok(()));
+        assert_eq!(queue.dequeue(), Some(1));
+        assert_eq!(queue.enqueue(3), Ok(()));
+        assert_eq!(queue.enqueue(4), Ok(()));
+        assert!(queue.is_full());
+        assert_eq!(queue.enqueue(5), Err(5));
+
+        assert_eq!(queue.peek(), Some(2));
+        assert_eq!(queue.dequeue(), Some(2));
+        assert_eq!(queue.dequeue(), Some(3));
+        assert_eq!(queue.dequeue(), Some(4));
+        assert!(queue.is_empty());
+        assert_eq!(queue.dequeue(), None);
+    }
+
+    #[test]
+    fn interrupt_fills_and_drains_queues() {
+        static mut RX: [u8; 2] = [0; 2];
+        static mut TX: [u8; 4] = [0; 4];
+        let mut buffers = Buffers::new(unsafe { &mut RX }, unsafe { &mut TX });
+        let mut usart = Mock::new(&[1, 2, 3], 2);
+        usart.rx_error = Some(Error::Noise);
+
+        for &byte in &[4, 5, 6] {
+            assert!(buffers.write(&mut usart, byte).is_ok());
+        }
+        assert!(usart.txe);
+
+        buffers.on_interrupt(&mut usart);
+        assert_eq!(buffers.rx_overflows, 1);
+        assert_eq!(usart.sent(), &[4, 5]);
+        assert!(usart.txe);
+
+        // The error is reported before the bytes received after it
+        assert!(matches!(buffers.read(), Err(nb::Error::Other(Error::Noise))));
+        assert_eq!(buffers.read().ok(), Some(1));
+        assert_eq!(buffers.read().ok(), Some(2));
+        assert!(matches!(buffers.read(), Err(nb::Error::WouldBlock)));
+        assert!(matches!(buffers.flush(&mut usart), Err(nb::Error::WouldBlock)));
+
+        usart.tx_room = 8;
+        buffers.on_interrupt(&mut usart);
+        assert_eq!(usart.sent(), &[4, 5, 6]);
+        assert!(!usart.txe);
+        assert!(buffers.flush(&mut usart).is_ok());
+    }
+
+    #[test]
+    fn transmission_error_is_reported_once() {
+        static mut RX: [u8; 2] = [0; 2];
+        static mut TX: [u8; 4] = [0; 4];
+        let mut buffers = Buffers::new(unsafe { &mut RX }, unsafe { &mut TX });
+        let mut usart = Mock::new(&[], 0);
+        usart.tx_error = Some(Error::ClearToSend);
+
+        assert!(buffers.write(&mut usart, 1).is_ok());
+        buffers.on_interrupt(&mut usart);
+        assert!(usart.txe);
+
+        // Reported by `write` without queuing the byte, then by `flush`
+        assert!(matches!(buffers.write(&mut usart, 2), Err(nb::Error::Other(Error::ClearToSend))));
+        assert_eq!(buffers.tx.len(), 1);
+        assert!(buffers.write(&mut usart, 2).is_ok());
+
+        usart.tx_error = Some(Error::ClearToSend);
+        buffers.on_interrupt(&mut usart);
+        assert!(matches!(buffers.flush(&mut usart), Err(nb::Error::Other(Error::ClearToSend))));
+        assert!(matches!(buffers.flush(&mut usart), Err(nb::Error::WouldBlock)));
+
+        // The byte refused on error is retried
+        usart.tx_room = 8;
+        buffers.on_interrupt(&mut usart);
+        assert_eq!(usart.sent(), &[1, 2]);
+        assert!(buffers.flush(&mut usart).is_ok());
+    }
+
+    #[test]
+    fn fmt_write_counts_dropped_bytes() {
+        static mut RX: [u8; 2] = [0; 2];
+        static mut TX: [u8; 4] = [0; 4];
+        let mut buffers = Buffers::new(unsafe { &mut RX }, unsafe { &mut TX });
+        let mut usart = Mock::new(&[], 8);
+
+        assert!(buffers.write_str(&mut usart, "hello").is_err());
+        assert_eq!(buffers.tx_overflows, 1);
+        assert!(usart.txe);
+
+        buffers.on_interrupt(&mut usart);
+        assert_eq!(usart.sent(), b"hell");
+        assert!(buffers.write_str(&mut usart, "o").is_ok());
+        assert_eq!(buffers.tx_overflows, 1);
+    }
+}
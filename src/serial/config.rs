@@ -0,0 +1,201 @@
+//! Serial configuration values
+
+use hal::spi::Mode;
+
+use time::Bps;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Word length, including the parity bit if any
+///
+/// E.g. 7E1 frames are `Bits8` with `Parity::Even`, while 9-bit data without parity is `Bits9`.
+pub enum WordLength {
+    /// 7 bits (M = 10)
+    Bits7,
+    /// 8 bits (M = 00)
+    Bits8,
+    /// 9 bits (M = 01)
+    Bits9,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Parity control
+pub enum Parity {
+    /// No parity bit
+    None,
+    /// Even parity, the MSB of the word is the parity bit
+    Even,
+    /// Odd parity, the MSB of the word is the parity bit
+    Odd,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+/// Number of stop bits, values are those of CR2.STOP
+pub enum StopBits {
+    /// 1 stop bit
+    Stop1 = 0b00,
+    /// 0.5 stop bit, not available on LPUART1
+    Stop0p5 = 0b01,
+    /// 2 stop bits
+    Stop2 = 0b10,
+    /// 1.5 stop bits, not available on LPUART1
+    Stop1p5 = 0b11,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Oversampling of the receiver
+///
+/// Oversampling by 8 allows twice the baud rate for a given clock, at the cost of a lower
+/// tolerance to clock deviation.
+pub enum Oversampling {
+    /// 16 samples per bit
+    Over16,
+    /// 8 samples per bit, not available on LPUART1
+    Over8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+#[repr(u8)]
+/// Automatic baud rate detection mode, values are those of CR2.ABRMOD
+///
+/// The character used for the detection is received normally.
+pub enum AutoBaudMode {
+    /// Measures the start bit, the character must start with a 1 bit
+    StartBit = 0b00,
+    /// Measures from falling edge to falling edge, the character must start with a 10 bit pattern
+    FallingEdge = 0b01,
+    /// Detects a 0x7F frame, which allows baud rates up to half the maximum
+    Frame0x7F = 0b10,
+    /// Detects a 0x55 frame, which is the most accurate
+    Frame0x55 = 0b11,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// IrDA SIR power mode
+pub enum IrdaMode {
+    /// Pulses of 3/16 of a bit
+    Normal,
+    /// Pulses of 3 periods of a 1.42 to 2.12 MHz clock divided down from the kernel clock
+    LowPower,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// ISO 7816-3 smartcard configuration
+///
+/// The frames are 8 data bits with even parity, which is set by `Serial::enable_smartcard`. The
+/// baud rate of `Config` is the one of the card, i.e. its clock frequency divided by F/D (372 at
+/// reset).
+pub struct SmartCardConfig {
+    /// Divides the kernel clock to produce the card clock on CK, which is `clk_f / (2 * prescaler)`;
+    /// 1 to 31
+    pub clock_prescaler: u8,
+    /// Extra guard time after each transmitted character, in bits
+    pub guard_time: u8,
+    /// Signals parity errors of received characters to the card
+    pub nack: bool,
+    /// Number of retransmissions of a character refused by the card, and of receptions of a
+    /// character with a parity error before reporting it; 0 to 7
+    pub retries: u8,
+}
+
+impl Default for SmartCardConfig {
+    /// 4 MHz card clock from a 80 MHz kernel clock, no extra guard time, 3 retries with NACK
+    fn default() -> Self {
+        SmartCardConfig {
+            clock_prescaler: 10,
+            guard_time: 0,
+            nack: true,
+            retries: 3,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+/// Clock output of the synchronous master mode
+pub struct SyncConfig {
+    /// Polarity and phase of the clock
+    pub mode: Mode,
+    /// Outputs a clock pulse for the last data bit
+    pub last_bit_clock: bool,
+}
+
+/// Smartcard configuration error
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SmartCardConfigError {
+    /// The clock prescaler isn't within 1 and 31
+    ClockPrescaler,
+    /// There are more than 7 retries
+    Retries,
+    #[doc(hidden)] _Extensible,
+}
+
+impl SmartCardConfig {
+    /// Checks that the fields fit in their registers
+    pub(crate) fn validate(&self) -> Result<(), SmartCardConfigError> {
+        if self.clock_prescaler == 0 || self.clock_prescaler > 31 {
+            Err(SmartCardConfigError::ClockPrescaler)
+        } else if self.retries > 7 {
+            Err(SmartCardConfigError::Retries)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+/// Serial configuration
+///
+/// The default is 9600 bps 8N1, LSB first, with an oversampling of 16, a baud rate tolerance of 2%
+/// and overrun detection.
+///
+/// ```rust
+/// let config = Config {
+///     baud_rate: 1200.bps(),
+///     word_length: WordLength::Bits8,
+///     parity: Parity::Even,
+///     ..Config::default()
+/// };
+/// ```
+pub struct Config {
+    /// Baud rate
+    pub baud_rate: Bps,
+    /// Word length
+    pub word_length: WordLength,
+    /// Parity
+    pub parity: Parity,
+    /// Stop bits
+    pub stop_bits: StopBits,
+    /// Oversampling
+    pub oversampling: Oversampling,
+    /// Transmits and receives the most significant bit first
+    pub msb_first: bool,
+    /// Maximum deviation of the achieved baud rate, in parts per million
+    pub baud_tolerance_ppm: u32,
+    /// Reports overruns; otherwise the received word overwrites the unread one silently
+    pub overrun_detection: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            baud_rate: Bps(9_600),
+            word_length: WordLength::Bits8,
+            parity: Parity::None,
+            stop_bits: StopBits::Stop1,
+            oversampling: Oversampling::Over16,
+            msb_first: false,
+            baud_tolerance_ppm: 20_000,
+            overrun_detection: true,
+        }
+    }
+}
+
+impl From<Bps> for Config {
+    /// 8N1 at `baud_rate`
+    fn from(baud_rate: Bps) -> Self {
+        Config {
+            baud_rate,
+            ..Config::default()
+        }
+    }
+}
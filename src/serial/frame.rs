@@ -0,0 +1,537 @@
+//! Reception of variable-length frames with DMA
+//!
+//! The DMA channel writes the received bytes into one of two buffers. The end of a frame is
+//! detected by the IDLE line interrupt, or by the receiver timeout of the USARTs, and when the
+//! buffer is full, which raises the transfer complete interrupt of the DMA channel.
+//! `FrameReader::on_interrupt`, called from both the USART and the DMA channel interrupt handlers,
+//! then restarts the transfer on the other buffer and returns the frame; the application hands its
+//! buffer back with `FrameReader::release`.
+//!
+//! ```rust
+//! static mut BUFFERS: [[u8; 64]; 2] = [[0; 64]; 2];
+//!
+//! let (buffer, spare) = unsafe { BUFFERS.split_at_mut(1) };
+//! let mut reader = rx.frame_reader(channels.6, &mut buffer[0], &mut spare[0]);
+//!
+//! // In the USART and the DMA channel interrupt handlers
+//! if let Some(frame) = reader.on_interrupt() {
+//!     process(&frame);
+//!     reader.release(frame);
+//! }
+//! ```
+
+use core::marker::Unsize;
+use core::mem;
+use core::ops::Deref;
+use core::sync::atomic::{self, Ordering};
+
+use stm32l4x6::{LPUART1, USART1, USART2, USART3, UART4, UART5};
+
+use dma::{dma1, dma2};
+use super::Rx;
+
+/// A received frame, whose buffer must be given back to the `FrameReader`
+pub struct Frame<B: 'static> {
+    buffer: &'static mut B,
+    len: usize,
+    truncated: bool,
+    overrun: bool,
+    merged: bool,
+}
+
+impl<B> Frame<B> {
+    /// Returns true if the frame filled the buffer before it ended
+    ///
+    /// The rest of the frame is returned as the next frame.
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns true if bytes were lost since the previous frame
+    ///
+    /// This happens when the buffer fills up while the previous frame hasn't been released.
+    pub fn is_overrun(&self) -> bool {
+        self.overrun
+    }
+
+    /// Returns true if the buffer holds several frames, whose boundaries are lost
+    ///
+    /// This happens when frames end while the previous frame hasn't been released.
+    pub fn is_merged(&self) -> bool {
+        self.merged
+    }
+}
+
+impl<B> Deref for Frame<B>
+where
+    B: Unsize<[u8]>,
+{
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        let buffer: &[u8] = self.buffer;
+        &buffer[..self.len]
+    }
+}
+
+/// DMA receiver of variable-length frames
+pub struct FrameReader<USART, B: 'static, CHANNEL> {
+    link: Link<USART, CHANNEL>,
+    buffers: Buffers<B>,
+}
+
+// Receiver and DMA channel of a `FrameReader`
+struct Link<USART, CHANNEL> {
+    rx: Rx<USART>,
+    channel: CHANNEL,
+}
+
+// Events that end a frame or lose bytes
+#[derive(Clone, Copy, Default)]
+struct Events {
+    idle: bool,
+    timeout: bool,
+    full: bool,
+    overrun: bool,
+}
+
+// DMA reception of a `FrameReader`, mocked by the tests
+trait Dma {
+    // Returns and clears the pending events
+    fn take_events(&mut self) -> Events;
+    // Starts receiving into `buffer`
+    fn start(&mut self, buffer: &mut [u8]);
+    fn stop(&mut self);
+    // Returns the number of bytes the transfer has yet to receive
+    fn remaining(&self) -> usize;
+}
+
+// Buffers and pending flags of a `FrameReader`
+struct Buffers<B: 'static> {
+    buffer: Option<&'static mut B>,
+    spare: Option<&'static mut B>,
+    overrun: bool,
+    merged: bool,
+}
+
+impl<B> Buffers<B>
+where
+    B: Unsize<[u8]>,
+{
+    fn new<D: Dma>(dma: &mut D, buffer: &'static mut B, spare: &'static mut B) -> Self {
+        let mut buffers = Buffers {
+            buffer: None,
+            spare: Some(spare),
+            overrun: false,
+            merged: false,
+        };
+        buffers.start(dma, buffer);
+        buffers
+    }
+
+    fn on_interrupt<D: Dma>(&mut self, dma: &mut D) -> Option<Frame<B>> {
+        let events = dma.take_events();
+        if events.overrun {
+            self.overrun = true;
+        }
+        let end = events.idle || events.timeout;
+        if !(end || events.full) {
+            return None;
+        }
+
+        let spare = match self.spare.take() {
+            Some(spare) => spare,
+            None => {
+                // The reception goes on in the same buffer, after the frame that just ended
+                if end && self.received(dma) > 0 && dma.remaining() > 0 {
+                    self.merged = true;
+                }
+                return None;
+            }
+        };
+        dma.stop();
+        let len = self.received(dma);
+        let buffer = self.buffer.take().unwrap();
+        if len == 0 {
+            self.spare = Some(spare);
+            self.start(dma, buffer);
+            return None;
+        }
+
+        self.start(dma, spare);
+        Some(Frame {
+            buffer,
+            len,
+            truncated: events.full && !end,
+            overrun: mem::replace(&mut self.overrun, false),
+            merged: mem::replace(&mut self.merged, false),
+        })
+    }
+
+    fn release(&mut self, frame: Frame<B>) {
+        self.spare = Some(frame.buffer);
+    }
+
+    fn start<D: Dma>(&mut self, dma: &mut D, buffer: &'static mut B) {
+        dma.start(buffer);
+        self.buffer = Some(buffer);
+    }
+
+    // Returns the number of bytes received into the current buffer
+    fn received<D: Dma>(&self, dma: &D) -> usize {
+        let buffer: &[u8] = &**self.buffer.as_ref().unwrap();
+        buffer.len() - dma.remaining()
+    }
+}
+
+macro_rules! frame_reader {
+    ($($USARTX:ident: $rx_chan:path,)+) => {
+        $(
+            impl Rx<$USARTX> {
+                /// Starts receiving frames into `buffer` and `spare` in turn
+                ///
+                /// Enables the IDLE line interrupt, which ends a frame when the line stays idle for
+                /// a word.
+                pub fn frame_reader<B>(
+                    self,
+                    channel: $rx_chan,
+                    buffer: &'static mut B,
+                    spare: &'static mut B,
+                ) -> FrameReader<$USARTX, B, $rx_chan>
+                where
+                    B: Unsize<[u8]>,
+                {
+                    let mut link = Link { rx: self, channel };
+                    let buffers = Buffers::new(&mut link, buffer, spare);
+
+                    // NOTE(unsafe) this half owns the receiver
+                    unsafe {
+                        (*$USARTX::ptr()).icr.write(|w| w.idlecf().set_bit());
+                        (*$USARTX::ptr()).cr3.modify(|_,w| w.dmar().set_bit());
+                        (*$USARTX::ptr()).cr1.modify(|_,w| w.idleie().set_bit());
+                    }
+
+                    FrameReader { link, buffers }
+                }
+            }
+
+            impl<B> FrameReader<$USARTX, B, $rx_chan>
+            where
+                B: Unsize<[u8]>,
+            {
+                /// Returns the frame that just ended, if any
+                ///
+                /// If the buffer of the previous frame hasn't been released yet, the reception goes
+                /// on in the same buffer: the frames that end meanwhile are returned together,
+                /// flagged as merged. Once that buffer is full, further bytes are lost and the next
+                /// frame is flagged as overrun.
+                pub fn on_interrupt(&mut self) -> Option<Frame<B>> {
+                    self.buffers.on_interrupt(&mut self.link)
+                }
+
+                /// Gives back the buffer of a frame, to receive the frame after the next one
+                pub fn release(&mut self, frame: Frame<B>) {
+                    self.buffers.release(frame)
+                }
+
+                /// Stops the reception and releases the receiver, the DMA channel and the buffers
+                ///
+                /// The spare buffer is `None` if a frame hasn't been released.
+                pub fn free(mut self) -> (Rx<$USARTX>, $rx_chan, &'static mut B, Option<&'static mut B>) {
+                    self.link.stop();
+                    self.link.channel.ccr().modify(|_, w| w.tcie().clear_bit());
+                    // NOTE(unsafe) this half owns the receiver
+                    unsafe {
+                        (*$USARTX::ptr()).cr1.modify(|_,w| w.idleie().clear_bit());
+                        (*$USARTX::ptr()).cr3.modify(|_,w| w.dmar().clear_bit());
+                    }
+                    (self.link.rx, self.link.channel, self.buffers.buffer.take().unwrap(), self.buffers.spare)
+                }
+            }
+
+            impl Dma for Link<$USARTX, $rx_chan> {
+                fn take_events(&mut self) -> Events {
+                    // NOTE(unsafe) atomic read with no side effects
+                    let isr = unsafe { (*$USARTX::ptr()).isr.read() };
+                    let events = Events {
+                        idle: isr.idle().bit_is_set(),
+                        timeout: Self::take_timeout(),
+                        full: self.channel.is_complete(),
+                        // The receiver stopped with an unread byte while the DMA transfer was over
+                        overrun: isr.ore().bit_is_set(),
+                    };
+                    // NOTE(unsafe) atomic write to stateless register
+                    unsafe {
+                        (*$USARTX::ptr()).icr.write(|w| w.idlecf().bit(events.idle).orecf().bit(events.overrun))
+                    };
+                    if events.full {
+                        self.channel.clear_flags();
+                    }
+                    events
+                }
+
+                fn start(&mut self, buffer: &mut [u8]) {
+                    self.channel.cmar().write(|w| unsafe {
+                        w.ma().bits(buffer.as_ptr() as usize as u32)
+                    });
+                    self.channel.cndtr().write(|w| unsafe {
+                        w.ndt().bits(buffer.len() as u16)
+                    });
+                    self.channel.cpar().write(|w| unsafe {
+                        w.pa().bits(&(*$USARTX::ptr()).rdr as *const _ as usize as u32)
+                    });
+
+                    // NOTE(compiler_fence) operations on the buffer should not be reordered after
+                    // the next statement, which starts the DMA transfer
+                    atomic::compiler_fence(Ordering::SeqCst);
+
+                    self.channel.ccr().modify(|_, w| unsafe {
+                        w.mem2mem()
+                            .clear_bit()
+                            .pl()
+                            .bits(0b10)
+                            .msize()
+                            .bits(0b00)
+                            .psize()
+                            .bits(0b00)
+                            .minc()
+                            .set_bit()
+                            .pinc()
+                            .clear_bit()
+                            .circ()
+                            .clear_bit()
+                            .dir()
+                            .clear_bit()
+                            .tcie()
+                            .set_bit()
+                            .en()
+                            .set_bit()
+                    });
+                }
+
+                fn stop(&mut self) {
+                    self.channel.ccr().modify(|_, w| w.en().clear_bit());
+                    while self.channel.ccr().read().en().bit_is_set() {}
+
+                    // NOTE(compiler_fence) operations on the buffer should not be reordered before
+                    // the previous statement, which stops the DMA transfer
+                    atomic::compiler_fence(Ordering::SeqCst);
+                }
+
+                fn remaining(&self) -> usize {
+                    self.channel.get_cndtr() as usize
+                }
+            }
+        )+
+    }
+}
+
+frame_reader! {
+    USART1: dma2::C7,
+    USART2: dma1::C6,
+    USART3: dma1::C3,
+    UART4: dma2::C5,
+    UART5: dma2::C2,
+    LPUART1: dma2::C7,
+}
+
+macro_rules! receiver_timeout {
+    ($($USARTX:ident,)+) => {
+        $(
+            impl<B, CHANNEL> FrameReader<$USARTX, B, CHANNEL> {
+                /// Also ends frames when the line stays idle for `bits` bit durations
+                ///
+                /// Unlike the IDLE line detection, the timeout is counted from the end of the last
+                /// stop bit, so it tolerates gaps between the words of a frame.
+                pub fn listen_timeout(&mut self, bits: u32) {
+                    // NOTE(unsafe) this half owns the receiver
+                    unsafe {
+                        (*$USARTX::ptr()).rtor.modify(|_,w| w.rto().bits(bits));
+                        (*$USARTX::ptr()).icr.write(|w| w.rtocf().set_bit());
+                        (*$USARTX::ptr()).cr2.modify(|_,w| w.rtoen().set_bit());
+                        (*$USARTX::ptr()).cr1.modify(|_,w| w.rtoie().set_bit());
+                    }
+                }
+
+                /// Stops ending frames on the receiver timeout
+                pub fn unlisten_timeout(&mut self) {
+                    // NOTE(unsafe) this half owns the receiver
+                    unsafe {
+                        (*$USARTX::ptr()).cr1.modify(|_,w| w.rtoie().clear_bit());
+                        (*$USARTX::ptr()).cr2.modify(|_,w| w.rtoen().clear_bit());
+                    }
+                }
+            }
+
+            impl<CHANNEL> Link<$USARTX, CHANNEL> {
+                fn take_timeout() -> bool {
+                    // NOTE(unsafe) atomic read with no side effects
+                    let timeout = unsafe { (*$USARTX::ptr()).isr.read().rtof().bit_is_set() };
+                    if timeout {
+                        // NOTE(unsafe) atomic write to stateless register
+                        unsafe { (*$USARTX::ptr()).icr.write(|w| w.rtocf().set_bit()) };
+                    }
+                    timeout
+                }
+            }
+        )+
+    }
+}
+
+receiver_timeout! {
+    USART1,
+    USART2,
+    USART3,
+    UART4,
+    UART5,
+}
+
+impl<CHANNEL> Link<LPUART1, CHANNEL> {
+    // LPUART1 has no receiver timeout
+    fn take_timeout() -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem;
+
+    use super::{Buffers, Dma, Events};
+
+    // Receives into the buffer of the last `start`, and reports `events` once
+    #[derive(Default)]
+    struct Mock {
+        events: Events,
+        started: usize,
+        starts: usize,
+        remaining: usize,
+        running: bool,
+    }
+
+    impl Mock {
+        fn receive(&mut self, bytes: usize, events: Events) {
+            assert!(self.running);
+            self.remaining -= bytes;
+            self.events = events;
+        }
+    }
+
+    impl Dma for Mock {
+        fn take_events(&mut self) -> Events {
+            mem::replace(&mut self.events, Events::default())
+        }
+
+        fn start(&mut self, buffer: &mut [u8]) {
+            self.started = buffer.as_ptr() as usize;
+            self.starts += 1;
+            self.remaining = buffer.len();
+            self.running = true;
+        }
+
+        fn stop(&mut self) {
+            self.running = false;
+        }
+
+        fn remaining(&self) -> usize {
+            self.remaining
+        }
+    }
+
+    const IDLE: Events = Events { idle: true, timeout: false, full: false, overrun: false };
+    const TIMEOUT: Events = Events { idle: false, timeout: true, full: false, overrun: false };
+    const FULL: Events = Events { idle: false, timeout: false, full: true, overrun: false };
+    const OVERRUN: Events = Events { idle: false, timeout: false, full: false, overrun: true };
+
+    #[test]
+    fn frames_swap_buffers() {
+        static mut FIRST: [u8; 4] = [1, 2, 3, 4];
+        static mut SECOND: [u8; 4] = [5, 6, 7, 8];
+        let (first, second) = unsafe { (FIRST.as_ptr() as usize, SECOND.as_ptr() as usize) };
+        let mut dma = Mock::default();
+        let mut buffers = Buffers::new(&mut dma, unsafe { &mut FIRST }, unsafe { &mut SECOND });
+        assert_eq!(dma.started, first);
+
+        dma.receive(2, IDLE);
+        let frame = buffers.on_interrupt(&mut dma).unwrap();
+        assert_eq!(&*frame, &[1, 2]);
+        assert!(!frame.is_truncated() && !frame.is_overrun() && !frame.is_merged());
+        assert_eq!(dma.started, second);
+        assert!(buffers.on_interrupt(&mut dma).is_none());
+        buffers.release(frame);
+
+        // An empty frame restarts the same buffer
+        dma.receive(0, IDLE);
+        assert!(buffers.on_interrupt(&mut dma).is_none());
+        assert_eq!((dma.started, dma.starts), (second, 3));
+
+        // A frame that fills the buffer goes on in the other one
+        dma.receive(4, FULL);
+        let frame = buffers.on_interrupt(&mut dma).unwrap();
+        assert_eq!(&*frame, &[5, 6, 7, 8]);
+        assert!(frame.is_truncated() && !frame.is_overrun() && !frame.is_merged());
+        assert_eq!(dma.started, first);
+        buffers.release(frame);
+
+        dma.receive(1, TIMEOUT);
+        let frame = buffers.on_interrupt(&mut dma).unwrap();
+        assert_eq!(&*frame, &[1]);
+        assert!(!frame.is_truncated());
+    }
+
+    #[test]
+    fn unreleased_frame_merges_next_frames() {
+        static mut FIRST: [u8; 4] = [0; 4];
+        static mut SECOND: [u8; 4] = [0; 4];
+        let mut dma = Mock::default();
+        let mut buffers = Buffers::new(&mut dma, unsafe { &mut FIRST }, unsafe { &mut SECOND });
+
+        dma.receive(1, IDLE);
+        let frame = buffers.on_interrupt(&mut dma).unwrap();
+
+        dma.receive(1, IDLE);
+        assert!(buffers.on_interrupt(&mut dma).is_none());
+        dma.receive(1, TIMEOUT);
+        assert!(buffers.on_interrupt(&mut dma).is_none());
+        assert!(dma.running);
+
+        buffers.release(frame);
+        dma.receive(1, IDLE);
+        let frame = buffers.on_interrupt(&mut dma).unwrap();
+        assert_eq!(frame.len(), 3);
+        assert!(frame.is_merged() && !frame.is_overrun());
+
+        buffers.release(frame);
+        dma.receive(1, IDLE);
+        assert!(!buffers.on_interrupt(&mut dma).unwrap().is_merged());
+    }
+
+    #[test]
+    fn lost_bytes_flag_next_frame_overrun() {
+        static mut FIRST: [u8; 2] = [0; 2];
+        static mut SECOND: [u8; 2] = [0; 2];
+        let mut dma = Mock::default();
+        let mut buffers = Buffers::new(&mut dma, unsafe { &mut FIRST }, unsafe { &mut SECOND });
+
+        dma.receive(1, IDLE);
+        let frame = buffers.on_interrupt(&mut dma).unwrap();
+
+        // The buffer fills up and the receiver overruns, the frame ends in a full buffer
+        dma.receive(2, FULL);
+        assert!(buffers.on_interrupt(&mut dma).is_none());
+        dma.receive(0, OVERRUN);
+        assert!(buffers.on_interrupt(&mut dma).is_none());
+        dma.receive(0, IDLE);
+        assert!(buffers.on_interrupt(&mut dma).is_none());
+
+        buffers.release(frame);
+        dma.receive(0, IDLE);
+        let frame = buffers.on_interrupt(&mut dma).unwrap();
+        assert_eq!(frame.len(), 2);
+        assert!(frame.is_overrun() && !frame.is_merged() && !frame.is_truncated());
+
+        buffers.release(frame);
+        dma.receive(1, IDLE);
+        assert!(!buffers.on_interrupt(&mut dma).unwrap().is_overrun());
+    }
+}
@@ -0,0 +1,139 @@
+//! LIN frames
+//!
+//! A frame is a header sent by the master (break, sync field 0x55 and protected identifier)
+//! followed by a response of up to 8 data bytes and a checksum, sent by the master or a slave.
+//! The USART side is implemented by the `lin_*` methods of `Serial`.
+//!
+//! See LIN Specification Package Revision 2.2A, Ch. 2.3
+
+use super::Error;
+
+/// Sync field of the header
+pub const SYNC: u8 = 0x55;
+
+/// Length of the break detected by the receiver
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BreakDetection {
+    /// 10 low bits (LBDL = 0)
+    Bits10,
+    /// 11 low bits (LBDL = 1)
+    Bits11,
+}
+
+/// Checksum model
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ChecksumModel {
+    /// Sum of the data bytes, used by LIN 1.x and for the diagnostic frames 0x3C and 0x3D
+    Classic,
+    /// Sum of the protected identifier and the data bytes, used by LIN 2.x
+    Enhanced,
+}
+
+// Progress of the reception of a header by `Serial::lin_read_header`
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum Header {
+    // Waiting for a break
+    Idle,
+    // Waiting for the sync field
+    Break,
+    // Waiting for the protected identifier
+    Sync,
+}
+
+/// LIN error
+#[derive(Debug)]
+pub enum LinError {
+    /// Error of the underlying serial peripheral
+    Serial(Error),
+    /// The sync field of the header isn't 0x55
+    Sync(u8),
+    /// The parity bits of the received protected identifier are wrong
+    Parity(u8),
+    /// The checksum of the response is wrong
+    Checksum,
+    #[doc(hidden)] _Extensible,
+}
+
+impl From<Error> for LinError {
+    fn from(error: Error) -> Self {
+        LinError::Serial(error)
+    }
+}
+
+/// Computes the protected identifier of the 6-bit frame identifier `id`
+///
+/// The upper two bits of `id` are ignored.
+pub fn protected_id(id: u8) -> u8 {
+    let id = id & 0x3F;
+    let bit = |n: u8| (id >> n) & 1;
+    let p0 = bit(0) ^ bit(1) ^ bit(2) ^ bit(4);
+    let p1 = !(bit(1) ^ bit(3) ^ bit(4) ^ bit(5)) & 1;
+
+    id | p0 << 6 | p1 << 7
+}
+
+/// Returns the frame identifier of a protected identifier, or `None` if its parity is wrong
+pub fn frame_id(pid: u8) -> Option<u8> {
+    match protected_id(pid) == pid {
+        true => Some(pid & 0x3F),
+        false => None,
+    }
+}
+
+/// Computes the checksum of a response
+///
+/// `pid` is only included with the enhanced model.
+pub fn checksum(model: ChecksumModel, pid: u8, data: &[u8]) -> u8 {
+    let init = match model {
+        ChecksumModel::Classic => 0,
+        ChecksumModel::Enhanced => pid as u16,
+    };
+    // Sum with carry: every carry out of the byte is added back to the LSB
+    let sum = data.iter().fold(init, |sum, &byte| {
+        let sum = sum + byte as u16;
+        match sum > 0xFF {
+            true => sum - 0xFF,
+            false => sum,
+        }
+    });
+
+    !(sum as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{checksum, frame_id, protected_id, ChecksumModel};
+
+    #[test]
+    fn protected_id_parity() {
+        assert_eq!(protected_id(0x00), 0x80);
+        assert_eq!(protected_id(0x01), 0xC1);
+        assert_eq!(protected_id(0x10), 0x50);
+        assert_eq!(protected_id(0x3C), 0x3C);
+        assert_eq!(protected_id(0x3D), 0x7D);
+        assert_eq!(protected_id(0x3F), 0xBF);
+        assert_eq!(protected_id(0xC1), protected_id(0x01));
+
+        assert_eq!(frame_id(0x7D), Some(0x3D));
+        assert_eq!(frame_id(0x3D), None);
+        for id in 0..0x40 {
+            assert_eq!(frame_id(protected_id(id)), Some(id));
+        }
+    }
+
+    #[test]
+    fn checksum_models() {
+        assert_eq!(checksum(ChecksumModel::Classic, 0x50, &[0x01, 0x02, 0x03]), 0xF9);
+        assert_eq!(checksum(ChecksumModel::Enhanced, 0x50, &[0x01, 0x02, 0x03]), 0xA9);
+        assert_eq!(checksum(ChecksumModel::Classic, 0x00, &[]), 0xFF);
+
+        // Carries are added back
+        assert_eq!(checksum(ChecksumModel::Classic, 0x00, &[0xFF, 0x01]), 0xFE);
+        assert_eq!(checksum(ChecksumModel::Enhanced, 0x4A, &[0x55, 0x93, 0xE5]), 0xE6);
+
+        // The checksum completes the sum to 0xFF
+        let frame = [0x12, 0xF0, 0x80, 0x7F, 0xAA, 0x55, 0x01, 0xFE, 0x7C];
+        assert_eq!(checksum(ChecksumModel::Enhanced, 0x80, &frame[..8]), 0x7C);
+        assert_eq!(checksum(ChecksumModel::Enhanced, 0x80, &frame), 0x00);
+    }
+}
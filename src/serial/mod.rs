@@ -6,18 +6,27 @@ use core::marker::{PhantomData, Unsize};
 use core::sync::atomic::{self, Ordering};
 
 use hal::serial;
+use hal::spi::{self, Phase, Polarity};
 use nb;
-use stm32l4x6::{USART1, USART2, USART3, UART4, UART5};
+use stm32l4x6::{lpuart1, usart1, LPUART1, USART1, USART2, USART3, UART4, UART5};
 
 use rcc::{APB1, APB2, CCIPR};
-use rcc::clocking::{USARTClkSource, InputClock};
 use time::Bps;
+use rcc::clocking::{USARTClkSource, InputClock};
 use dma::{dma1, dma2, CircBuffer, Static, Transfer, R, W};
+use gpio::{AltFun, AltFunPin, OpenDrain, PullUp};
+
+pub mod baud;
+pub mod buffered;
+pub mod config;
+pub mod frame;
+pub mod lin;
 
-#[cfg(feature = "STM32L496AG")]
-pub mod stm32l496ag;
-#[cfg(feature = "STM32L496AG")]
-pub use self::stm32l496ag::*;
+pub use self::baud::{Baud, BaudRateError};
+pub use self::buffered::{Buffered, Queue};
+pub use self::config::{AutoBaudMode, Config, IrdaMode, Oversampling, Parity, SmartCardConfig, SmartCardConfigError, StopBits, SyncConfig, WordLength};
+pub use self::frame::{Frame, FrameReader};
+pub use self::lin::{BreakDetection, ChecksumModel, LinError};
 
 /// Interrupt event
 pub enum Event {
@@ -29,6 +38,17 @@ pub enum Event {
     Idle,
 }
 
+/// Event waking LPUART1 up from Stop mode
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum WakeupEvent {
+    /// Reception of the given 7-bit address, marked by the MSB of the word
+    AddressMatch(u8),
+    /// Detection of a start bit
+    StartBit,
+    /// Reception of a word
+    Rxne,
+}
+
 /// Serial error
 #[derive(Debug)]
 pub enum Error {
@@ -40,25 +60,284 @@ pub enum Error {
     Overrun,
     /// Parity check error
     Parity,
+    /// The receiver deasserted CTS, transmission is paused until it asserts it again
+    ClearToSend,
+    /// The smartcard refused a character (NACK) more times than the configured retries
+    Nack,
+    /// Automatic baud rate detection failed, e.g. the baud rate is out of range
+    AutoBaudRate,
     #[doc(hidden)] _Extensible,
 }
 
 // FIXME these should be "closed" traits
 /// TX pin - DO NOT IMPLEMENT THIS TRAIT
+///
+/// # Safety
+///
+/// The pin must carry the TX signal of `USART` in its alternate function.
 pub unsafe trait TxPin<USART> {}
 
 /// RX pin - DO NOT IMPLEMENT THIS TRAIT
+///
+/// # Safety
+///
+/// The pin must carry the RX signal of `USART` in its alternate function.
 pub unsafe trait RxPin<USART> {}
 
+/// CTS pin - DO NOT IMPLEMENT THIS TRAIT
+///
+/// # Safety
+///
+/// The pin must carry the CTS signal of `USART` in its alternate function.
+pub unsafe trait CtsPin<USART> {}
+
+/// RTS/DE pin - DO NOT IMPLEMENT THIS TRAIT
+///
+/// # Safety
+///
+/// The pin must carry the RTS/DE signal of `USART` in its alternate function.
+pub unsafe trait RtsPin<USART> {}
+
+/// CK pin - DO NOT IMPLEMENT THIS TRAIT
+///
+/// # Safety
+///
+/// The pin must carry the CK signal of `USART` in its alternate function.
+pub unsafe trait CkPin<USART> {}
+
+// Implementations for the pins of each chip are in `gpio::af`.
+
+/// Placeholder for an unused RTS pin
+pub struct NoRts;
+
+/// Placeholder for an unused CTS pin
+pub struct NoCts;
+
+/// Polarity of the driver enable signal
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DePolarity {
+    /// DE is high while transmitting
+    ActiveHigh,
+    /// DE is low while transmitting
+    ActiveLow,
+}
+
+/// Assertion or deassertion time of the driver enable signal
+///
+/// In sample time units (1/16 or 1/8 of a bit depending on the oversampling), from 0 to 31.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct DeTime(u8);
+
+impl DeTime {
+    /// Returns the time of `samples` sample times, or `None` if it doesn't fit in DEAT/DEDT
+    pub fn new(samples: u8) -> Option<Self> {
+        match samples < 32 {
+            true => Some(DeTime(samples)),
+            false => None,
+        }
+    }
+
+    /// Returns the number of sample times
+    pub fn samples(self) -> u8 {
+        self.0
+    }
+}
+
+/// Driver enable pin of a RS-485 transceiver, driven by the RTS signal
+///
+/// The assertion and deassertion times are the delays between the activation of DE and the start
+/// bit, and between the end of the last stop bit and the deactivation of DE.
+pub struct DriverEnable<DE> {
+    /// RTS pin wired to DE
+    pub pin: DE,
+    /// Polarity of DE
+    pub polarity: DePolarity,
+    /// Assertion time (DEAT)
+    pub assertion_time: DeTime,
+    /// Deassertion time (DEDT)
+    pub deassertion_time: DeTime,
+}
+
+impl<DE> DriverEnable<DE> {
+    /// Active high DE, asserted and deasserted without delay
+    pub fn new(pin: DE) -> Self {
+        DriverEnable {
+            pin,
+            polarity: DePolarity::ActiveHigh,
+            assertion_time: DeTime(0),
+            deassertion_time: DeTime(0),
+        }
+    }
+}
+
+/// Single wire of a half-duplex serial link
+///
+/// The TX pin carries both directions, so it must be configured as open-drain alternate function
+/// with the internal pull-up, e.g. `PA2<AF7<OpenDrain, PullUp>>`. `Serial` switches between
+/// transmission and reception itself, see its `Read` and `Write` implementations for `HalfDuplex`
+/// pins.
+pub struct HalfDuplex<TX>(pub TX);
+
+/// Pins of a smartcard interface
+///
+/// The IO pin is the TX pin, which must be configured as open-drain alternate function with the
+/// internal pull-up, e.g. `PA9<AF7<OpenDrain, PullUp>>`. The clock of the card is output on CK.
+pub struct SmartCard<IO, CK> {
+    /// Data line
+    pub io: IO,
+    /// Clock line
+    pub ck: CK,
+}
+
+/// Set of pins of a serial peripheral - DO NOT IMPLEMENT THIS TRAIT
+///
+/// Implemented for:
+///
+/// - `(TX, RX)`: no hardware flow control
+/// - `(TX, RX, RTS, CTS)`: RTS and CTS flow control, either of them may be `NoRts`/`NoCts`
+/// - `(TX, RX, DriverEnable<DE>)`: RS-485 driver enable mode
+/// - `HalfDuplex<TX>`: single-wire half-duplex mode
+/// - `SmartCard<IO, CK>`: smartcard mode, see `Serial::enable_smartcard` (USART1 to USART3)
+/// - `(TX, RX, CK)`: synchronous master mode, see `Serial::usart1_synchronous` (USART1 to USART3)
+///
+/// # Safety
+///
+/// Each pin of the set must carry its signal of `USART`, and `setup` must configure the
+/// peripheral for the mode of the set.
+pub unsafe trait Pins<USART> {
+    #[doc(hidden)]
+    /// Configures the peripheral for the pins, while it is disabled
+    fn setup(&self, usart: &USART);
+}
+
+macro_rules! pins {
+    ($USARTX:ident) => {
+        unsafe impl<TX, RX> Pins<$USARTX> for (TX, RX)
+        where
+            TX: TxPin<$USARTX>,
+            RX: RxPin<$USARTX>,
+        {
+            fn setup(&self, usart: &$USARTX) {
+                usart.cr3.modify(|_,w| w.rtse().clear_bit().ctse().clear_bit().dem().clear_bit()); // no hardware flow control
+            }
+        }
+
+        unsafe impl<TX, RX, RTS, CTS> Pins<$USARTX> for (TX, RX, RTS, CTS)
+        where
+            TX: TxPin<$USARTX>,
+            RX: RxPin<$USARTX>,
+            RTS: RtsPin<$USARTX>,
+            CTS: CtsPin<$USARTX>,
+        {
+            fn setup(&self, usart: &$USARTX) {
+                usart.cr3.modify(|_,w| w.rtse().set_bit().ctse().set_bit().dem().clear_bit());
+            }
+        }
+
+        unsafe impl<TX, RX, RTS> Pins<$USARTX> for (TX, RX, RTS, NoCts)
+        where
+            TX: TxPin<$USARTX>,
+            RX: RxPin<$USARTX>,
+            RTS: RtsPin<$USARTX>,
+        {
+            fn setup(&self, usart: &$USARTX) {
+                usart.cr3.modify(|_,w| w.rtse().set_bit().ctse().clear_bit().dem().clear_bit());
+            }
+        }
+
+        unsafe impl<TX, RX, CTS> Pins<$USARTX> for (TX, RX, NoRts, CTS)
+        where
+            TX: TxPin<$USARTX>,
+            RX: RxPin<$USARTX>,
+            CTS: CtsPin<$USARTX>,
+        {
+            fn setup(&self, usart: &$USARTX) {
+                usart.cr3.modify(|_,w| w.rtse().clear_bit().ctse().set_bit().dem().clear_bit());
+            }
+        }
+
+        unsafe impl<TX> Pins<$USARTX> for HalfDuplex<TX>
+        where
+            TX: TxPin<$USARTX> + AltFunPin,
+            TX::AltFun: AltFun<Output = OpenDrain, Pull = PullUp>,
+        {
+            fn setup(&self, usart: &$USARTX) {
+                usart.cr3.modify(|_,w| w.rtse().clear_bit().ctse().clear_bit().dem().clear_bit().hdsel().set_bit());
+            }
+        }
+
+        unsafe impl<TX, RX, DE> Pins<$USARTX> for (TX, RX, DriverEnable<DE>)
+        where
+            TX: TxPin<$USARTX>,
+            RX: RxPin<$USARTX>,
+            DE: RtsPin<$USARTX>,
+        {
+            fn setup(&self, usart: &$USARTX) {
+                let de = &self.2;
+                let (deat, dedt) = (de.assertion_time.samples(), de.deassertion_time.samples());
+
+                usart.cr1.modify(|_,w| {
+                    w.deat0().bit(deat & 0b00001 != 0)
+                     .deat1().bit(deat & 0b00010 != 0)
+                     .deat2().bit(deat & 0b00100 != 0)
+                     .deat3().bit(deat & 0b01000 != 0)
+                     .deat4().bit(deat & 0b10000 != 0)
+                     .dedt0().bit(dedt & 0b00001 != 0)
+                     .dedt1().bit(dedt & 0b00010 != 0)
+                     .dedt2().bit(dedt & 0b00100 != 0)
+                     .dedt3().bit(dedt & 0b01000 != 0)
+                     .dedt4().bit(dedt & 0b10000 != 0)
+                });
+                usart.cr3.modify(|_,w| {
+                    w.rtse().clear_bit()
+                     .ctse().clear_bit()
+                     .dem().set_bit()
+                     .dep().bit(de.polarity == DePolarity::ActiveLow)
+                });
+            }
+        }
+    }
+}
+
+/// Number of reception errors of each kind
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ErrorCounters {
+    /// Parity errors
+    pub parity: u32,
+    /// Framing errors
+    pub framing: u32,
+    /// Noise errors
+    pub noise: u32,
+    /// Overruns
+    pub overrun: u32,
+}
+
+impl ErrorCounters {
+    fn count(&mut self, error: &Error) {
+        let counter = match *error {
+            Error::Parity => &mut self.parity,
+            Error::Framing => &mut self.framing,
+            Error::Noise => &mut self.noise,
+            Error::Overrun => &mut self.overrun,
+            _ => return,
+        };
+        *counter = counter.wrapping_add(1);
+    }
+}
+
 /// Serial abstraction
 pub struct Serial<USART, PINS> {
     usart: USART,
     pins: PINS,
+    clk_f: u32,
+    errors: ErrorCounters,
+    lin_header: lin::Header,
 }
 
 /// Serial receiver
 pub struct Rx<USART> {
     _usart: PhantomData<USART>,
+    errors: ErrorCounters,
 }
 
 /// Serial transmitter
@@ -66,6 +345,523 @@ pub struct Tx<USART> {
     _usart: PhantomData<USART>,
 }
 
+// Returns the M1, M0, PCE and PS bits of CR1
+fn frame_bits(config: &Config) -> (bool, bool, bool, bool) {
+    let (m1, m0) = match config.word_length {
+        WordLength::Bits7 => (true, false),
+        WordLength::Bits8 => (false, false),
+        WordLength::Bits9 => (false, true),
+    };
+    let (pce, ps) = match config.parity {
+        Parity::None => (false, false),
+        Parity::Even => (true, false),
+        Parity::Odd => (true, true),
+    };
+
+    (m1, m0, pce, ps)
+}
+
+fn apply_config(usart: &usart1::RegisterBlock, config: &Config, baud: &Baud) {
+    let (m1, m0, pce, ps) = frame_bits(config);
+    let over8 = config.oversampling == Oversampling::Over8;
+
+    // From UART_SetConfig:
+    usart.cr1.modify(|_,w| w
+                     .m1().bit(m1)
+                     .m0().bit(m0)
+                     .pce().bit(pce)
+                     .ps().bit(ps)
+                     .te().set_bit()      // enable tx
+                     .re().set_bit()      // enable rx
+                     .over8().bit(over8)
+                    );
+    usart.cr2.modify(|_,w| unsafe { w.stop().bits(config.stop_bits as u8).msbfirst().bit(config.msb_first) });
+    usart.cr3.modify(|_,w| w.ovrdis().bit(!config.overrun_detection));
+
+    usart.brr.write(|w| unsafe { w.bits(baud.brr) });
+}
+
+// LPUART1 has neither 0.5 and 1.5 stop bits nor OVER8
+fn check_lpuart_config(config: &Config) -> Result<(), BaudRateError> {
+    match (config.stop_bits, config.oversampling) {
+        (StopBits::Stop1, Oversampling::Over16) | (StopBits::Stop2, Oversampling::Over16) => Ok(()),
+        _ => Err(BaudRateError::Unsupported),
+    }
+}
+
+fn apply_lpuart_config(lpuart: &lpuart1::RegisterBlock, config: &Config, baud: &Baud) {
+    let (m1, m0, pce, ps) = frame_bits(config);
+
+    lpuart.cr1.modify(|_,w| w
+                      .m1().bit(m1)
+                      .m0().bit(m0)
+                      .pce().bit(pce)
+                      .ps().bit(ps)
+                      .te().set_bit()
+                      .re().set_bit()
+                     );
+    lpuart.cr2.modify(|_,w| unsafe { w.stop().bits(config.stop_bits as u8).msbfirst().bit(config.msb_first) });
+    lpuart.cr3.modify(|_,w| w.ovrdis().bit(!config.overrun_detection));
+
+    lpuart.brr.write(|w| unsafe { w.bits(baud.brr) });
+}
+
+// Received words carry the parity bit as MSB, which is not part of the data
+fn data_mask(m1: bool, m0: bool, pce: bool) -> u16 {
+    let bits = match (m1, m0) {
+        (true, _) => 7,
+        (false, false) => 8,
+        (false, true) => 9,
+    };
+    let bits = match pce {
+        true => bits - 1,
+        false => bits,
+    };
+
+    (1 << bits) - 1
+}
+
+macro_rules! common {
+    ($USARTX:ident, rx: $rx_chan:path, tx: $tx_chan:path) => {
+        impl<PINS> Serial<$USARTX, PINS> {
+            // Waits for the end of the ongoing transmission, then applies `f` while the peripheral
+            // is disabled, as most configuration bits are only writable then
+            fn with_disabled<F>(&mut self, f: F)
+            where
+                F: FnOnce(&$USARTX),
+            {
+                while self.usart.isr.read().tc().bit_is_clear() {}
+
+                self.usart.cr1.modify(|_,w| w.ue().clear_bit());
+                f(&self.usart);
+                self.usart.cr1.modify(|_,w| w.ue().set_bit());
+
+                while self.usart.isr.read().teack().bit_is_clear() {}
+                while self.usart.isr.read().reack().bit_is_clear() {}
+            }
+
+            /// Starts listening for an interrupt event
+            pub fn listen(&mut self, event: Event) {
+                match event {
+                    Event::Rxne => self.usart.cr1.modify(|_, w| w.rxneie().set_bit()),
+                    Event::Txe => self.usart.cr1.modify(|_, w| w.txeie().set_bit()),
+                    Event::Idle => self.usart.cr1.modify(|_, w| w.idleie().set_bit()),
+                }
+            }
+
+            /// Starts listening for an interrupt event
+            pub fn unlisten(&mut self, event: Event) {
+                match event {
+                    Event::Rxne => self.usart.cr1.modify(|_, w| w.rxneie().clear_bit()),
+                    Event::Txe => self.usart.cr1.modify(|_, w| w.txeie().clear_bit()),
+                    Event::Idle => self.usart.cr1.modify(|_, w| w.idleie().clear_bit()),
+                }
+            }
+
+            /// Splits the `Serial` abstraction into a transmitter and a receiver half
+            ///
+            /// The error counters move to the receiver, which goes on counting from the errors
+            /// counted so far.
+            pub fn split(self) -> (Tx<$USARTX>, Rx<$USARTX>) {
+                (
+                    Tx {
+                        _usart: PhantomData,
+                    },
+                    Rx {
+                        _usart: PhantomData,
+                        errors: self.errors,
+                    },
+                )
+            }
+
+            /// Returns the number of reception errors since the last reset of the counters
+            pub fn errors(&self) -> ErrorCounters {
+                self.errors
+            }
+
+            /// Resets the reception error counters
+            pub fn reset_errors(&mut self) {
+                self.errors = ErrorCounters::default();
+            }
+
+            /// Releases the USART peripheral and associated pins
+            pub fn free(self) -> ($USARTX, PINS) {
+                (self.usart, self.pins)
+            }
+        }
+
+        impl serial::Read<u8> for Rx<$USARTX> {
+            type Error = Error;
+
+            fn read(&mut self) -> nb::Result<u8, Error> {
+                serial::Read::<u16>::read(self).map(|word| word as u8)
+            }
+        }
+
+        impl serial::Read<u16> for Rx<$USARTX> {
+            type Error = Error;
+
+            /// Reads a word of up to 9 bits, without the parity bit
+            ///
+            /// A parity, framing or noise error is reported once, the word received with it is
+            /// returned by the next read. After an overrun, the next read returns the word received
+            /// before the lost ones.
+            fn read(&mut self) -> nb::Result<u16, Error> {
+                Self::read_word(&mut self.errors)
+            }
+        }
+
+        impl Rx<$USARTX> {
+            /// Returns the number of reception errors since the last reset of the counters
+            ///
+            /// The counters are those of the `Serial` this receiver was split from.
+            pub fn errors(&self) -> ErrorCounters {
+                self.errors
+            }
+
+            /// Resets the reception error counters
+            pub fn reset_errors(&mut self) {
+                self.errors = ErrorCounters::default();
+            }
+
+            // Reads RDR, or reports and clears the first error flag
+            fn read_word(errors: &mut ErrorCounters) -> nb::Result<u16, Error> {
+                // NOTE(unsafe) atomic read with no side effects
+                let isr = unsafe { (*$USARTX::ptr()).isr.read() };
+
+                let error = if isr.pe().bit_is_set() {
+                    // NOTE(unsafe) atomic write to stateless register
+                    unsafe { (*$USARTX::ptr()).icr.write(|w| w.pecf().set_bit()) };
+                    Error::Parity
+                } else if isr.fe().bit_is_set() && isr.rxne().bit_is_set() {
+                    // Without a received word, FE is a NACK of the smartcard reported by `Tx`
+                    // NOTE(unsafe) atomic write to stateless register
+                    unsafe { (*$USARTX::ptr()).icr.write(|w| w.fecf().set_bit()) };
+                    Error::Framing
+                } else if isr.nf().bit_is_set() {
+                    // NOTE(unsafe) atomic write to stateless register
+                    unsafe { (*$USARTX::ptr()).icr.write(|w| w.ncf().set_bit()) };
+                    Error::Noise
+                } else if isr.ore().bit_is_set() {
+                    // NOTE(unsafe) atomic write to stateless register
+                    unsafe { (*$USARTX::ptr()).icr.write(|w| w.orecf().set_bit()) };
+                    Error::Overrun
+                } else if isr.rxne().bit_is_set() {
+                    // NOTE(unsafe) atomic read with no side effects
+                    let cr1 = unsafe { (*$USARTX::ptr()).cr1.read() };
+                    // NOTE(read_volatile) see `write_volatile` below
+                    let word: u16 = unsafe {
+                        ptr::read_volatile(&(*$USARTX::ptr()).rdr as *const _ as *const _)
+                    };
+                    return Ok(word & data_mask(cr1.m1().bit_is_set(), cr1.m0().bit_is_set(), cr1.pce().bit_is_set()));
+                } else {
+                    return Err(nb::Error::WouldBlock);
+                };
+
+                errors.count(&error);
+                Err(nb::Error::Other(error))
+            }
+        }
+
+        impl Rx<$USARTX> {
+            pub fn circ_read<B>(
+                self,
+                mut chan: $rx_chan,
+                buffer: &'static mut [B; 2],
+            ) -> CircBuffer<B, $rx_chan>
+            where
+                B: Unsize<[u8]>,
+            {
+                {
+                    let buffer: &[u8] = &buffer[0];
+                    chan.cmar().write(|w| unsafe {
+                        w.ma().bits(buffer.as_ptr() as usize as u32)
+                    });
+                    chan.cndtr().write(|w| unsafe{
+                        w.ndt().bits((buffer.len() * 2) as u16)
+                    });
+                    chan.cpar().write(|w| unsafe {
+                        w.pa().bits(&(*$USARTX::ptr()).rdr as *const _ as usize as u32)
+                    });
+
+                    // enable DMA rx on $USARTX
+                    unsafe {
+                        (*$USARTX::ptr()).cr3.modify(|_,w| w.dmar().set_bit());
+                    }
+
+                    // TODO can we weaken this compiler barrier?
+                    // NOTE(compiler_fence) operations on `buffer` should not be reordered after
+                    // the next statement, which starts the DMA transfer
+                    atomic::compiler_fence(Ordering::SeqCst);
+
+                    chan.ccr().modify(|_, w| unsafe {
+                        w.mem2mem()
+                            .clear_bit()
+                            .pl()
+                            .bits(0b10)
+                            .msize()
+                            .bits(0b00)
+                            .psize()
+                            .bits(0b00)
+                            .minc()
+                            .set_bit()
+                            .pinc()
+                            .clear_bit()
+                            .circ()
+                            .set_bit()
+                            .dir()
+                            .clear_bit()
+                            .en()
+                            .set_bit()
+                    });
+                }
+
+                CircBuffer::new(buffer, chan)
+            }
+
+            pub fn read_exact<B>(
+                self,
+                mut chan: $rx_chan,
+                buffer: &'static mut B,
+            ) -> Transfer<W, &'static mut B, $rx_chan, Self>
+            where
+                B: Unsize<[u8]>,
+            {
+                {
+                    let buffer: &[u8] = buffer;
+                    chan.cmar().write(|w| unsafe {
+                        w.ma().bits(buffer.as_ptr() as usize as u32)
+                    });
+                    chan.cndtr().write(|w| unsafe{
+                        w.ndt().bits((buffer.len()) as u16)
+                    });
+                    chan.cpar().write(|w| unsafe {
+                        w.pa().bits(&(*$USARTX::ptr()).rdr as *const _ as usize as u32)
+                    });
+
+                    // enable DMA rx on $USARTX
+                    unsafe {
+                        (*$USARTX::ptr()).cr3.modify(|_,w| w.dmar().set_bit());
+                    }
+
+                    // TODO can we weaken this compiler barrier?
+                    // NOTE(compiler_fence) operations on `buffer` should not be reordered after
+                    // the next statement, which starts the DMA transfer
+                    atomic::compiler_fence(Ordering::SeqCst);
+
+                    chan.ccr().modify(|_, w| unsafe {
+                        w.mem2mem()
+                            .clear_bit()
+                            .pl()
+                            .bits(0b10)
+                            .msize()
+                            .bits(0b00)
+                            .psize()
+                            .bits(0b00)
+                            .minc()
+                            .set_bit()
+                            .pinc()
+                            .clear_bit()
+                            .circ()
+                            .clear_bit()
+                            .dir()
+                            .clear_bit()
+                            .en()
+                            .set_bit()
+                    });
+                }
+
+                Transfer::w(buffer, chan, self)
+            }
+        }
+
+        impl Tx<$USARTX> {
+            pub fn write_all<A, B>(
+                self,
+                mut chan: $tx_chan,
+                buffer: B,
+            ) -> Transfer<R, B, $tx_chan, Self>
+            where
+                A: Unsize<[u8]>,
+                B: Static<A>,
+            {
+                {
+                    let buffer: &[u8] = buffer.borrow();
+                    chan.cmar().write(|w| unsafe {
+                        w.ma().bits(buffer.as_ptr() as usize as u32)
+                    });
+                    chan.cndtr().write(|w| unsafe{
+                        w.ndt().bits((buffer.len()) as u16)
+                    });
+                    chan.cpar().write(|w| unsafe {
+                        w.pa().bits(&(*$USARTX::ptr()).rdr as *const _ as usize as u32)
+                    });
+
+                    // enable DMA tx on $USARTX
+                    unsafe {
+                        (*$USARTX::ptr()).cr3.modify(|_,w| w.dmat().set_bit());
+                    }
+
+                    // TODO can we weaken this compiler barrier?
+                    // NOTE(compiler_fence) operations on `buffer` should not be reordered after
+                    // the next statement, which starts the DMA transfer
+                    atomic::compiler_fence(Ordering::SeqCst);
+
+                    chan.ccr().modify(|_, w| unsafe {
+                        w.mem2mem()
+                            .clear_bit()
+                            .pl()
+                            .bits(0b01)
+                            .msize()
+                            .bits(0b00)
+                            .psize()
+                            .bits(0b00)
+                            .minc()
+                            .set_bit()
+                            .pinc()
+                            .clear_bit()
+                            .circ()
+                            .clear_bit()
+                            .dir()
+                            .set_bit()
+                            .en()
+                            .set_bit()
+                    });
+                }
+
+                Transfer::r(buffer, chan, self)
+            }
+        }
+
+        impl<TX> serial::Read<u8> for Serial<$USARTX, HalfDuplex<TX>> {
+            type Error = Error;
+
+            fn read(&mut self) -> nb::Result<u8, Error> {
+                serial::Read::<u16>::read(self).map(|word| word as u8)
+            }
+        }
+
+        impl<TX> serial::Read<u16> for Serial<$USARTX, HalfDuplex<TX>> {
+            type Error = Error;
+
+            /// Switches to reception once the ongoing transmission is complete
+            fn read(&mut self) -> nb::Result<u16, Error> {
+                if self.usart.cr1.read().re().bit_is_clear() {
+                    if self.usart.isr.read().tc().bit_is_clear() {
+                        return Err(nb::Error::WouldBlock);
+                    }
+                    self.usart.cr1.modify(|_,w| w.re().set_bit());
+                }
+
+                Rx::<$USARTX>::read_word(&mut self.errors)
+            }
+        }
+
+        impl<TX> serial::Write<u8> for Serial<$USARTX, HalfDuplex<TX>> {
+            type Error = Error;
+
+            fn flush(&mut self) -> nb::Result<(), Error> {
+                serial::Write::<u16>::flush(self)
+            }
+
+            fn write(&mut self, byte: u8) -> nb::Result<(), Error> {
+                serial::Write::<u16>::write(self, byte as u16)
+            }
+        }
+
+        impl<TX> serial::Write<u16> for Serial<$USARTX, HalfDuplex<TX>> {
+            type Error = Error;
+
+            /// Waits for the end of the transmission and switches back to reception
+            fn flush(&mut self) -> nb::Result<(), Error> {
+                let mut tx: Tx<$USARTX> = Tx { _usart: PhantomData };
+                serial::Write::<u16>::flush(&mut tx)?;
+                self.usart.cr1.modify(|_,w| w.re().set_bit());
+                Ok(())
+            }
+
+            /// Switches to transmission, so that the receiver doesn't read back the echo of the
+            /// written words
+            fn write(&mut self, word: u16) -> nb::Result<(), Error> {
+                if self.usart.cr1.read().re().bit_is_set() {
+                    self.usart.cr1.modify(|_,w| w.re().clear_bit());
+                }
+
+                let mut tx: Tx<$USARTX> = Tx { _usart: PhantomData };
+                serial::Write::<u16>::write(&mut tx, word)
+            }
+        }
+
+        impl serial::Write<u8> for Tx<$USARTX> {
+            // NOTE(Error) See section "29.7 USART interrupts"; the only possible errors during
+            // transmission are: clear to send (when CTS flow control is enabled) errors and framing
+            // errors (which only occur in SmartCard mode, when the card refuses a character)
+            type Error = Error;
+
+            fn flush(&mut self) -> nb::Result<(), Error> {
+                serial::Write::<u16>::flush(self)
+            }
+
+            fn write(&mut self, byte: u8) -> nb::Result<(), Error> {
+                serial::Write::<u16>::write(self, byte as u16)
+            }
+        }
+
+        impl serial::Write<u16> for Tx<$USARTX> {
+            // NOTE(Error) see `Write<u8>`
+            type Error = Error;
+
+            fn flush(&mut self) -> nb::Result<(), Error> {
+                // NOTE(unsafe) atomic read with no side effects
+                let isr = unsafe { (*$USARTX::ptr()).isr.read() };
+
+                if isr.tc().bit_is_set() {
+                    Ok(())
+                } else {
+                    Err(nb::Error::WouldBlock)
+                }
+            }
+
+            /// Writes a word of up to 9 bits, bits beyond the word length are ignored
+            ///
+            /// Reports `Error::ClearToSend` once when the receiver deasserts CTS, the word can be
+            /// retried and will be sent once CTS is asserted again. In smartcard mode, reports
+            /// `Error::Nack` once when the card refused the previous character.
+            fn write(&mut self, word: u16) -> nb::Result<(), Error> {
+                // NOTE(unsafe) atomic read with no side effects
+                let isr = unsafe { (*$USARTX::ptr()).isr.read() };
+
+                if isr.ctsif().bit_is_set() {
+                    // NOTE(unsafe) atomic write to stateless register
+                    unsafe { (*$USARTX::ptr()).icr.write(|w| w.ctscf().set_bit()) };
+                    // CTSIF is set on both edges, ISR.CTS is the inverse of the nCTS input, i.e.
+                    // set while CTS is asserted
+                    if isr.cts().bit_is_clear() {
+                        return Err(nb::Error::Other(Error::ClearToSend));
+                    }
+                }
+
+                // FE comes with a received word on framing errors of the receiver, while the NACK
+                // of a transmitted character has none
+                if isr.fe().bit_is_set() && isr.rxne().bit_is_clear() && self.take_nack() {
+                    return Err(nb::Error::Other(Error::Nack));
+                }
+
+                if isr.txe().bit_is_set() {
+                    // NOTE(unsafe) atomic write to stateless register
+                    // NOTE(write_volatile) 16-bit write that's not possible through the svd2rust API
+                    unsafe {
+                        ptr::write_volatile(&(*$USARTX::ptr()).tdr as *const _ as *mut u16, word & 0x1FF)
+                    }
+                    Ok(())
+                } else {
+                    Err(nb::Error::WouldBlock)
+                }
+            }
+        }
+    }
+}
+
 macro_rules! hal {
     ($(
         $USARTX:ident: (
@@ -78,20 +874,46 @@ macro_rules! hal {
             tx: $tx_chan:path),
     )+) => {
         $(
-            impl<TX, RX> Serial<$USARTX, (TX, RX)> {
-                /// Configures the $USARTX peripheral to provide 8N1 asynchronous serial communication
-                /// with an oversampling rate of 16.
-                pub fn $usartX(
+            pins!($USARTX);
+
+            impl<PINS> Serial<$USARTX, PINS> {
+                /// Configures the $USARTX peripheral to provide asynchronous serial communication
+                ///
+                /// `config` is either a `Config` or a `Bps` for 8N1 with an oversampling rate of 16.
+                /// Hardware flow control is enabled by passing RTS and/or CTS pins, RS-485 driver
+                /// enable mode by passing a `DriverEnable` pin and single-wire half-duplex mode by
+                /// passing `HalfDuplex(tx)` (see `Pins`).
+                ///
+                /// Fails without touching the peripheral if the baud rate can not be achieved within
+                /// the tolerance of `config`.
+                pub fn $usartX<C>(
+                    usart: $USARTX,
+                    pins: PINS,
+                    config: C,
+                    clock: USARTClkSource,
+                    apb: &mut $APB,
+                    ccipr: &mut CCIPR,
+                ) -> Result<Self, BaudRateError>
+                where
+                    PINS: Pins<$USARTX>,
+                    C: Into<Config>,
+                {
+                    Self::init(usart, pins, config.into(), None, clock, apb, ccipr)
+                }
+
+                // Configures the peripheral while it is disabled, then enables it; `sync` enables
+                // the clock output of the synchronous master mode
+                fn init(
                     usart: $USARTX,
-                    pins: (TX, RX),
-                    baud_rate: Bps,
+                    pins: PINS,
+                    config: Config,
+                    sync: Option<SyncConfig>,
                     clock: USARTClkSource,
                     apb: &mut $APB,
                     ccipr: &mut CCIPR,
-                ) -> Self
+                ) -> Result<Self, BaudRateError>
                 where
-                    TX: TxPin<$USARTX>,
-                    RX: RxPin<$USARTX>,
+                    PINS: Pins<$USARTX>,
                 {
                     let (clk_f, sel_bits) = match clock {
                         USARTClkSource::PCLK(c)   => (c.freq(), 0b00),
@@ -99,6 +921,9 @@ macro_rules! hal {
                         USARTClkSource::HSI16(c)  => (c.freq(), 0b10),
                         USARTClkSource::LSE       => (32_768,   0b11),
                     };
+                    let baud = Baud::usart(clk_f, config.baud_rate, config.oversampling)?
+                        .within(config.baud_tolerance_ppm)?;
+
                     ccipr.inner().modify(|_,w| unsafe {
                         w.$usartXsel().bits(sel_bits)
                     }); // __HAL_RCC_USART2_CONFIG <- HAL_RCCEx_PeriphCLKConfig
@@ -109,303 +934,306 @@ macro_rules! hal {
                     usart.cr1.modify(|_,w| w.ue().clear_bit()); // __HAL_UART_DISABLE in HAL_UART_Init
                     // configuration bits can only be written when the usart peripheral is disabled
 
-                    // From UART_SetConfig:
-                    usart.cr1.modify(|_,w| w
-                                     .m1().clear_bit()
-                                     .m0().clear_bit()    // 8-bit word length
-                                     .pce().clear_bit()   // parity control disabled
-                                     .te().set_bit()      // enable tx
-                                     .re().set_bit()      // enable rx
-                                     .over8().clear_bit() // 16x oversampling
-                                    );
-                    usart.cr2.modify(|_,w| unsafe { w.stop().bits(0b00) });          // 1 stop bit
-                    usart.cr3.modify(|_,w| w.rtse().clear_bit().ctse().clear_bit()); // no hardware flow control
-
-                    let brr = clk_f / baud_rate.0; // 40.5.4 USART baud rate generation
-                    if brr < 16 {
-                        panic!("impossible BRR");
-                    }
-                    usart.brr.write(|w| unsafe { w.bits(brr) });
+                    apply_config(&usart, &config, &baud);
 
                     // In asynchronous mode, the following bits must be kept cleared:
                     // - LINEN and CLKEN bits in the USART_CR2 register,
                     // - SCEN, HDSEL and IREN  bits in the USART_CR3 register.
-                    usart.cr2.modify(|_,w| w.linen().clear_bit().clken().clear_bit());
+                    // The synchronous mode only differs by CLKEN.
+                    usart.cr2.modify(|_,w| match sync {
+                        Some(sync) => {
+                            w.linen().clear_bit()
+                             .cpol().bit(sync.mode.polarity == Polarity::IdleHigh)
+                             .cpha().bit(sync.mode.phase == Phase::CaptureOnSecondTransition)
+                             .lbcl().bit(sync.last_bit_clock)
+                             .clken().set_bit()
+                        }
+                        None => w.linen().clear_bit().clken().clear_bit(),
+                    });
                     usart.cr3.modify(|_,w| w.scen().clear_bit().hdsel().clear_bit().iren().clear_bit());
+                    // HDSEL is set back by half-duplex pins
+                    pins.setup(&usart);
 
                     usart.cr1.modify(|_,w| w.ue().set_bit()); // __HAL_UART_ENABLE in HAL_UART_Init
 
                     while usart.isr.read().teack().bit_is_clear() {} // UART_CheckIdleState in HAL_UART_Init
                     while usart.isr.read().reack().bit_is_clear() {}
 
-                    Serial { usart, pins }
+                    Ok(Serial {
+                        usart,
+                        pins,
+                        clk_f,
+                        errors: ErrorCounters::default(),
+                        lin_header: lin::Header::Idle,
+                    })
                 }
 
-                /// Starts listening for an interrupt event
-                pub fn listen(&mut self, event: Event) {
-                    match event {
-                        Event::Rxne => self.usart.cr1.modify(|_, w| w.rxneie().set_bit()),
-                        Event::Txe => self.usart.cr1.modify(|_, w| w.txeie().set_bit()),
-                        Event::Idle => self.usart.cr1.modify(|_, w| w.idleie().set_bit()),
+                /// Changes the frame format and baud rate
+                ///
+                /// Waits for the end of the ongoing transmission, then disables the peripheral while
+                /// the configuration is applied. Fails without touching the peripheral if the baud
+                /// rate can not be achieved within the tolerance of `config`.
+                pub fn reconfigure(&mut self, config: Config) -> Result<(), BaudRateError> {
+                    let baud = Baud::usart(self.clk_f, config.baud_rate, config.oversampling)?
+                        .within(config.baud_tolerance_ppm)?;
+                    if self.usart.cr3.read().iren().bit_is_set() {
+                        baud::irda_check_brr(self.clk_f, baud.brr, config.oversampling)?;
                     }
+
+                    self.with_disabled(|usart| {
+                        apply_config(usart, &config, &baud);
+                    });
+
+                    Ok(())
                 }
 
-                /// Starts listening for an interrupt event
-                pub fn unlisten(&mut self, event: Event) {
-                    match event {
-                        Event::Rxne => self.usart.cr1.modify(|_, w| w.rxneie().clear_bit()),
-                        Event::Txe => self.usart.cr1.modify(|_, w| w.txeie().clear_bit()),
-                        Event::Idle => self.usart.cr1.modify(|_, w| w.idleie().clear_bit()),
-                    }
+                /// Enables LIN mode
+                ///
+                /// Breaks are 13 bits long when sent and detected after `detection` low bits when
+                /// received. Frames are switched to 8N1, the format of LIN.
+                pub fn enable_lin(&mut self, detection: BreakDetection) {
+                    self.with_disabled(|usart| {
+                        usart.cr1.modify(|_,w| w.m1().clear_bit().m0().clear_bit().pce().clear_bit());
+                        // In LIN mode, the following bits must be kept cleared:
+                        // - STOP[1:0] and CLKEN in the USART_CR2 register,
+                        // - SCEN, HDSEL and IREN in the USART_CR3 register.
+                        usart.cr2.modify(|_,w| unsafe {
+                            w.stop().bits(0b00)
+                             .clken().clear_bit()
+                             .lbdl().bit(detection == BreakDetection::Bits11)
+                             .linen().set_bit()
+                        });
+                        usart.cr3.modify(|_,w| w.scen().clear_bit().hdsel().clear_bit().iren().clear_bit());
+                    });
+                    self.lin_header = lin::Header::Idle;
                 }
 
-                /// Splits the `Serial` abstraction into a transmitter and a receiver half
-                pub fn split(self) -> (Tx<$USARTX>, Rx<$USARTX>) {
-                    (
-                        Tx {
-                            _usart: PhantomData,
-                        },
-                        Rx {
-                            _usart: PhantomData,
-                        },
-                    )
+                /// Disables LIN mode
+                pub fn disable_lin(&mut self) {
+                    self.with_disabled(|usart| {
+                        usart.cr2.modify(|_,w| w.linen().clear_bit().lbdie().clear_bit());
+                    });
                 }
 
-                /// Releases the USART peripheral and associated pins
-                pub fn free(self) -> ($USARTX, (TX, RX)) {
-                    (self.usart, self.pins)
+                /// Starts listening for the LIN break detection interrupt
+                pub fn listen_lin_break(&mut self) {
+                    self.usart.cr2.modify(|_,w| w.lbdie().set_bit());
                 }
-            }
 
-            impl serial::Read<u8> for Rx<$USARTX> {
-                type Error = Error;
+                /// Stops listening for the LIN break detection interrupt
+                pub fn unlisten_lin_break(&mut self) {
+                    self.usart.cr2.modify(|_,w| w.lbdie().clear_bit());
+                }
 
-                fn read(&mut self) -> nb::Result<u8, Error> {
-                    // NOTE(unsafe) atomic read with no side effects
-                    let isr = unsafe { (*$USARTX::ptr()).isr.read() };
-
-                    Err(if isr.pe().bit_is_set() {
-                        nb::Error::Other(Error::Parity)
-                    } else if isr.fe().bit_is_set() {
-                        nb::Error::Other(Error::Framing)
-                    } else if isr.nf().bit_is_set() {
-                        nb::Error::Other(Error::Noise)
-                    } else if isr.ore().bit_is_set() {
-                        nb::Error::Other(Error::Overrun)
-                    } else if isr.rxne().bit_is_set() {
-                        // NOTE(read_volatile) see `write_volatile` below
-                        return Ok(unsafe {
-                            ptr::read_volatile(&(*$USARTX::ptr()).rdr as *const _ as *const _)
-                        });
-                    } else {
-                        nb::Error::WouldBlock
-                    })
+                /// Returns whether a LIN break has been detected, and clears the flag
+                pub fn lin_break_detected(&mut self) -> bool {
+                    let detected = self.usart.isr.read().lbdf().bit_is_set();
+                    self.usart.icr.write(|w| w.lbdcf().set_bit());
+                    detected
                 }
-            }
 
-            impl Rx<$USARTX> {
-                pub fn circ_read<B>(
-                    self,
-                    mut chan: $rx_chan,
-                    buffer: &'static mut [B; 2],
-                ) -> CircBuffer<B, $rx_chan>
-                where
-                    B: Unsize<[u8]>,
-                {
-                    {
-                        let buffer: &[u8] = &buffer[0];
-                        chan.cmar().write(|w| unsafe {
-                            w.ma().bits(buffer.as_ptr() as usize as u32)
-                        });
-                        chan.cndtr().write(|w| unsafe{
-                            w.ndt().bits((buffer.len() * 2) as u16)
-                        });
-                        chan.cpar().write(|w| unsafe {
-                            w.pa().bits(&(*$USARTX::ptr()).rdr as *const _ as usize as u32)
-                        });
+                /// Sends a LIN break, blocking until it has been sent
+                pub fn lin_send_break(&mut self) {
+                    while self.usart.isr.read().txe().bit_is_clear() {}
+                    self.usart.rqr.write(|w| w.sbkrq().set_bit());
+                    while self.usart.isr.read().sbkf().bit_is_set() {}
+                }
 
-                        // enable DMA rx on $USARTX
-                        unsafe {
-                            (*$USARTX::ptr()).cr3.modify(|_,w| w.dmar().set_bit());
-                        }
+                /// Sends the header of a frame as master: break, sync field and protected identifier
+                ///
+                /// The echo of the header on the bus is read back, so that the response can be read
+                /// right after.
+                pub fn lin_write_header(&mut self, id: u8) -> Result<(), LinError> {
+                    self.lin_send_break();
+                    self.lin_drop_break();
+                    self.lin_write_bytes(&[lin::SYNC, lin::protected_id(id)])
+                }
 
-                        // TODO can we weaken this compiler barrier?
-                        // NOTE(compiler_fence) operations on `buffer` should not be reordered after
-                        // the next statement, which starts the DMA transfer
-                        atomic::compiler_fence(Ordering::SeqCst);
-
-                        chan.ccr().modify(|_, w| unsafe {
-                            w.mem2mem()
-                                .clear_bit()
-                                .pl()
-                                .bits(0b10)
-                                .msize()
-                                .bits(0b00)
-                                .psize()
-                                .bits(0b00)
-                                .minc()
-                                .set_bit()
-                                .pinc()
-                                .clear_bit()
-                                .circ()
-                                .set_bit()
-                                .dir()
-                                .clear_bit()
-                                .en()
-                                .set_bit()
-                        });
+                /// Receives the header of a frame as slave and returns its frame identifier
+                ///
+                /// Returns `WouldBlock` until a break, the sync field and the protected identifier
+                /// have been received. A new break restarts the reception, and after an error the
+                /// next header starts with a break.
+                pub fn lin_read_header(&mut self) -> nb::Result<u8, LinError> {
+                    if self.lin_break_detected() {
+                        self.lin_drop_break();
+                        self.lin_header = lin::Header::Break;
                     }
 
-                    CircBuffer::new(buffer, chan)
-                }
+                    if self.lin_header == lin::Header::Idle {
+                        return Err(nb::Error::WouldBlock);
+                    }
+                    if self.lin_header == lin::Header::Break {
+                        let sync = self.lin_poll_byte()?;
+                        if sync != lin::SYNC {
+                            self.lin_header = lin::Header::Idle;
+                            return Err(nb::Error::Other(LinError::Sync(sync)));
+                        }
+                        self.lin_header = lin::Header::Sync;
+                    }
 
-                pub fn read_exact<B>(
-                    self,
-                    mut chan: $rx_chan,
-                    buffer: &'static mut B,
-                ) -> Transfer<W, &'static mut B, $rx_chan, Self>
-                where
-                    B: Unsize<[u8]>,
-                {
-                    {
-                        let buffer: &[u8] = buffer;
-                        chan.cmar().write(|w| unsafe {
-                            w.ma().bits(buffer.as_ptr() as usize as u32)
-                        });
-                        chan.cndtr().write(|w| unsafe{
-                            w.ndt().bits((buffer.len()) as u16)
-                        });
-                        chan.cpar().write(|w| unsafe {
-                            w.pa().bits(&(*$USARTX::ptr()).rdr as *const _ as usize as u32)
-                        });
+                    let pid = self.lin_poll_byte()?;
+                    self.lin_header = lin::Header::Idle;
+                    lin::frame_id(pid).ok_or(nb::Error::Other(LinError::Parity(pid)))
+                }
 
-                        // enable DMA rx on $USARTX
-                        unsafe {
-                            (*$USARTX::ptr()).cr3.modify(|_,w| w.dmar().set_bit());
-                        }
+                /// Sends the response of frame `id`: the data bytes and their checksum
+                pub fn lin_write_response(
+                    &mut self,
+                    id: u8,
+                    data: &[u8],
+                    model: ChecksumModel,
+                ) -> Result<(), LinError> {
+                    let checksum = lin::checksum(model, lin::protected_id(id), data);
+                    self.lin_write_bytes(data)?;
+                    self.lin_write_bytes(&[checksum])
+                }
 
-                        // TODO can we weaken this compiler barrier?
-                        // NOTE(compiler_fence) operations on `buffer` should not be reordered after
-                        // the next statement, which starts the DMA transfer
-                        atomic::compiler_fence(Ordering::SeqCst);
-
-                        chan.ccr().modify(|_, w| unsafe {
-                            w.mem2mem()
-                                .clear_bit()
-                                .pl()
-                                .bits(0b10)
-                                .msize()
-                                .bits(0b00)
-                                .psize()
-                                .bits(0b00)
-                                .minc()
-                                .set_bit()
-                                .pinc()
-                                .clear_bit()
-                                .circ()
-                                .clear_bit()
-                                .dir()
-                                .clear_bit()
-                                .en()
-                                .set_bit()
-                        });
+                /// Receives the response of frame `id` into `data` and checks its checksum
+                ///
+                /// The length of the response is that of `data`.
+                pub fn lin_read_response(
+                    &mut self,
+                    id: u8,
+                    data: &mut [u8],
+                    model: ChecksumModel,
+                ) -> Result<(), LinError> {
+                    for byte in data.iter_mut() {
+                        *byte = self.lin_read_byte()?;
+                    }
+                    let checksum = self.lin_read_byte()?;
+                    match lin::checksum(model, lin::protected_id(id), data) == checksum {
+                        true => Ok(()),
+                        false => Err(LinError::Checksum),
                     }
+                }
 
-                    Transfer::w(buffer, chan, self)
+                // Sends the bytes one by one, reading back the echo of each of them from the bus
+                fn lin_write_bytes(&mut self, bytes: &[u8]) -> Result<(), LinError> {
+                    let mut tx: Tx<$USARTX> = Tx { _usart: PhantomData };
+                    for &byte in bytes {
+                        block!(serial::Write::<u8>::write(&mut tx, byte))?;
+                        self.lin_read_byte()?;
+                    }
+                    Ok(())
                 }
-            }
 
-            impl Tx<$USARTX> {
-                pub fn write_all<A, B>(
-                    self,
-                    mut chan: $tx_chan,
-                    buffer: B,
-                ) -> Transfer<R, B, $tx_chan, Self>
-                where
-                    A: Unsize<[u8]>,
-                    B: Static<A>,
-                {
-                    {
-                        let buffer: &[u8] = buffer.borrow();
-                        chan.cmar().write(|w| unsafe {
-                            w.ma().bits(buffer.as_ptr() as usize as u32)
-                        });
-                        chan.cndtr().write(|w| unsafe{
-                            w.ndt().bits((buffer.len()) as u16)
-                        });
-                        chan.cpar().write(|w| unsafe {
-                            w.pa().bits(&(*$USARTX::ptr()).rdr as *const _ as usize as u32)
-                        });
+                fn lin_read_byte(&mut self) -> Result<u8, LinError> {
+                    Ok(block!(Rx::<$USARTX>::read_word(&mut self.errors))? as u8)
+                }
 
-                        // enable DMA tx on $USARTX
-                        unsafe {
-                            (*$USARTX::ptr()).cr3.modify(|_,w| w.dmat().set_bit());
+                // Returns the next byte of a header, an error ends the header
+                fn lin_poll_byte(&mut self) -> nb::Result<u8, LinError> {
+                    match Rx::<$USARTX>::read_word(&mut self.errors) {
+                        Ok(word) => Ok(word as u8),
+                        Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+                        Err(nb::Error::Other(error)) => {
+                            self.lin_header = lin::Header::Idle;
+                            Err(nb::Error::Other(error.into()))
                         }
+                    }
+                }
 
-                        // TODO can we weaken this compiler barrier?
-                        // NOTE(compiler_fence) operations on `buffer` should not be reordered after
-                        // the next statement, which starts the DMA transfer
-                        atomic::compiler_fence(Ordering::SeqCst);
-
-                        chan.ccr().modify(|_, w| unsafe {
-                            w.mem2mem()
-                                .clear_bit()
-                                .pl()
-                                .bits(0b01)
-                                .msize()
-                                .bits(0b00)
-                                .psize()
-                                .bits(0b00)
-                                .minc()
-                                .set_bit()
-                                .pinc()
-                                .clear_bit()
-                                .circ()
-                                .clear_bit()
-                                .dir()
-                                .set_bit()
-                                .en()
-                                .set_bit()
+                // Drops the 0x00 word with a framing error a break is also received as, which is
+                // there once the break is detected or sent
+                fn lin_drop_break(&mut self) {
+                    self.usart.icr.write(|w| w.fecf().set_bit().lbdcf().set_bit());
+                    self.usart.rqr.write(|w| w.rxfrq().set_bit());
+                }
+
+                /// Arms automatic baud rate detection on the next received character
+                ///
+                /// The baud rate of the first character must be within `clk_f / 65535` and
+                /// `clk_f / 16` (`clk_f / 8` with an oversampling of 8). Detection stays enabled, it
+                /// can be repeated with `rearm_auto_baud`.
+                pub fn enable_auto_baud(&mut self, mode: AutoBaudMode) {
+                    let mode = mode as u8;
+
+                    self.with_disabled(|usart| {
+                        usart.cr2.modify(|_,w| {
+                            w.abrmod0().bit(mode & 0b01 != 0)
+                             .abrmod1().bit(mode & 0b10 != 0)
+                             .abren().set_bit()
                         });
-                    }
+                    });
+                }
 
-                    Transfer::r(buffer, chan, self)
+                /// Disables automatic baud rate detection, the detected baud rate is kept
+                pub fn disable_auto_baud(&mut self) {
+                    self.with_disabled(|usart| {
+                        usart.cr2.modify(|_,w| w.abren().clear_bit());
+                    });
                 }
-            }
 
-            impl serial::Write<u8> for Tx<$USARTX> {
-                // NOTE(!) See section "29.7 USART interrupts"; the only possible errors during transmission
-                // are: clear to send (which is disabled in this case) errors and framing errors (which only
-                // occur in SmartCard mode); neither of these apply to our hardware configuration
-                type Error = !;
+                /// Restarts automatic baud rate detection on the next received character
+                pub fn rearm_auto_baud(&mut self) {
+                    self.usart.rqr.write(|w| w.abrrq().set_bit());
+                }
 
-                fn flush(&mut self) -> nb::Result<(), !> {
-                    // NOTE(unsafe) atomic read with no side effects
-                    let isr = unsafe { (*$USARTX::ptr()).isr.read() };
+                /// Returns the detected baud rate once detection completed
+                ///
+                /// The character used for the detection is then available through `Read`.
+                pub fn auto_baud(&mut self) -> nb::Result<Bps, Error> {
+                    let isr = self.usart.isr.read();
 
-                    if isr.tc().bit_is_set() {
-                        Ok(())
+                    if isr.abre().bit_is_set() {
+                        Err(nb::Error::Other(Error::AutoBaudRate))
+                    } else if isr.abrf().bit_is_set() {
+                        Ok(Baud::from_brr(self.clk_f, self.usart.brr.read().bits(), self.oversampling()))
                     } else {
                         Err(nb::Error::WouldBlock)
                     }
                 }
 
-                fn write(&mut self, byte: u8) -> nb::Result<(), !> {
-                    // NOTE(unsafe) atomic read with no side effects
-                    let isr = unsafe { (*$USARTX::ptr()).isr.read() };
-
-                    if isr.txe().bit_is_set() {
-                        // NOTE(unsafe) atomic write to stateless register
-                        // NOTE(write_volatile) 8-bit write that's not possible through the svd2rust API
-                        unsafe {
-                            ptr::write_volatile(&(*$USARTX::ptr()).tdr as *const _ as *mut _, byte)
-                        }
-                        Ok(())
-                    } else {
-                        Err(nb::Error::WouldBlock)
+                fn oversampling(&self) -> Oversampling {
+                    match self.usart.cr1.read().over8().bit_is_set() {
+                        true => Oversampling::Over8,
+                        false => Oversampling::Over16,
                     }
                 }
+
+                /// Enables the IrDA SIR encoder and decoder
+                ///
+                /// Frames must have 1 stop bit. Fails without touching the peripheral with
+                /// `BaudRateError::Unsupported` if the baud rate exceeds 115200, which `reconfigure`
+                /// also enforces while IrDA is enabled. In low-power mode, the kernel clock is
+                /// divided down to about 1.8432 MHz, which fails likewise if it is out of the 1.42 to
+                /// 2.12 MHz range.
+                pub fn enable_irda(&mut self, mode: IrdaMode) -> Result<(), BaudRateError> {
+                    baud::irda_check_brr(self.clk_f, self.usart.brr.read().bits(), self.oversampling())?;
+                    let psc = match mode {
+                        IrdaMode::Normal => 1,
+                        IrdaMode::LowPower => baud::irda_low_power_prescaler(self.clk_f)?,
+                    };
+
+                    self.with_disabled(|usart| {
+                        // In IrDA mode, the following bits must be kept cleared:
+                        // - LINEN, STOP and CLKEN bits in the USART_CR2 register,
+                        // - SCEN and HDSEL bits in the USART_CR3 register.
+                        usart.cr2.modify(|_,w| unsafe {
+                            w.linen().clear_bit().stop().bits(0b00).clken().clear_bit()
+                        });
+                        usart.gtpr.modify(|_,w| unsafe { w.psc().bits(psc) });
+                        usart.cr3.modify(|_,w| {
+                            w.scen().clear_bit()
+                             .hdsel().clear_bit()
+                             .irlp().bit(mode == IrdaMode::LowPower)
+                             .iren().set_bit()
+                        });
+                    });
+
+                    Ok(())
+                }
+
+                /// Disables the IrDA SIR encoder and decoder
+                pub fn disable_irda(&mut self) {
+                    self.with_disabled(|usart| {
+                        usart.cr3.modify(|_,w| w.iren().clear_bit().irlp().clear_bit());
+                    });
+                }
             }
+
+            common!($USARTX, rx: $rx_chan, tx: $tx_chan);
         )+
     }
 }
@@ -417,3 +1245,314 @@ hal! {
     UART4:  (uart4,  APB1, enr1, uart4en,  uart4sel,  rx: dma2::C5, tx: dma2::C3),
     UART5:  (uart5,  APB1, enr1, uart5en,  uart5sel,  rx: dma2::C2, tx: dma2::C1),
 }
+
+// Synchronous and smartcard modes, which need the CK pin of the USARTs
+macro_rules! synchronous {
+    ($(
+        $USARTX:ident: ($usartX:ident, $usartX_synchronous:ident, $APB:ident),
+    )+) => {
+        $(
+            unsafe impl<TX, RX, CK> Pins<$USARTX> for (TX, RX, CK)
+            where
+                TX: TxPin<$USARTX>,
+                RX: RxPin<$USARTX>,
+                CK: CkPin<$USARTX>,
+            {
+                fn setup(&self, usart: &$USARTX) {
+                    usart.cr3.modify(|_,w| w.rtse().clear_bit().ctse().clear_bit().dem().clear_bit());
+                }
+            }
+
+            unsafe impl<IO, CK> Pins<$USARTX> for SmartCard<IO, CK>
+            where
+                IO: TxPin<$USARTX> + AltFunPin,
+                IO::AltFun: AltFun<Output = OpenDrain, Pull = PullUp>,
+                CK: CkPin<$USARTX>,
+            {
+                fn setup(&self, usart: &$USARTX) {
+                    usart.cr3.modify(|_,w| w.rtse().clear_bit().ctse().clear_bit().dem().clear_bit());
+                }
+            }
+
+            impl<TX, RX, CK> Serial<$USARTX, (TX, RX, CK)>
+            where
+                TX: TxPin<$USARTX>,
+                RX: RxPin<$USARTX>,
+                CK: CkPin<$USARTX>,
+            {
+                /// Configures the $USARTX peripheral as synchronous master, with the clock output on CK
+                ///
+                /// The clock runs only while a word is transmitted and its frequency is the baud
+                /// rate of `config`. `sync` sets its polarity and phase, and whether a clock pulse is
+                /// output for the last data bit. Like SPI, data is received only while transmitting,
+                /// see the `spi::FullDuplex` implementation.
+                pub fn $usartX_synchronous<C>(
+                    usart: $USARTX,
+                    pins: (TX, RX, CK),
+                    config: C,
+                    sync: SyncConfig,
+                    clock: USARTClkSource,
+                    apb: &mut $APB,
+                    ccipr: &mut CCIPR,
+                ) -> Result<Self, BaudRateError>
+                where
+                    C: Into<Config>,
+                {
+                    Self::init(usart, pins, config.into(), Some(sync), clock, apb, ccipr)
+                }
+            }
+
+            impl<TX, RX, CK> spi::FullDuplex<u8> for Serial<$USARTX, (TX, RX, CK)>
+            where
+                CK: CkPin<$USARTX>,
+            {
+                type Error = Error;
+
+                fn read(&mut self) -> nb::Result<u8, Error> {
+                    Rx::<$USARTX>::read_word(&mut self.errors).map(|word| word as u8)
+                }
+
+                fn send(&mut self, byte: u8) -> nb::Result<(), Error> {
+                    let mut tx: Tx<$USARTX> = Tx { _usart: PhantomData };
+                    serial::Write::<u8>::write(&mut tx, byte)
+                }
+            }
+
+            impl Tx<$USARTX> {
+                // Clears FE without a received word if it reports a NACK, i.e. in smartcard mode
+                fn take_nack(&mut self) -> bool {
+                    // NOTE(unsafe) atomic read with no side effects
+                    if unsafe { (*$USARTX::ptr()).cr3.read().scen().bit_is_clear() } {
+                        return false;
+                    }
+                    // NOTE(unsafe) atomic write to stateless register
+                    unsafe { (*$USARTX::ptr()).icr.write(|w| w.fecf().set_bit()) };
+                    true
+                }
+            }
+
+            impl<IO, CK> Serial<$USARTX, SmartCard<IO, CK>> {
+                /// Enables smartcard mode and starts the card clock
+                ///
+                /// Frames are switched to 8 data bits with even parity and 1.5 stop bits. The card is
+                /// then reset through its RST line, which is a GPIO, and answers with its ATR, see
+                /// `read_atr`.
+                ///
+                /// Fails without touching the peripheral if `sc` is out of range.
+                pub fn enable_smartcard(&mut self, sc: SmartCardConfig) -> Result<(), SmartCardConfigError> {
+                    sc.validate()?;
+
+                    self.with_disabled(|usart| {
+                        usart.cr1.modify(|_,w| {
+                            w.m1().clear_bit().m0().set_bit().pce().set_bit().ps().clear_bit()
+                        });
+                        // In smartcard mode, the following bits must be kept cleared:
+                        // - LINEN bit in the USART_CR2 register,
+                        // - HDSEL and IREN bits in the USART_CR3 register.
+                        usart.cr2.modify(|_,w| unsafe {
+                            w.linen().clear_bit().stop().bits(StopBits::Stop1p5 as u8).clken().set_bit()
+                        });
+                        usart.gtpr.write(|w| unsafe {
+                            w.psc().bits(sc.clock_prescaler).gt().bits(sc.guard_time)
+                        });
+                        usart.cr3.modify(|_,w| unsafe {
+                            w.hdsel().clear_bit()
+                             .iren().clear_bit()
+                             .nack().bit(sc.nack)
+                             .scarcnt().bits(sc.retries)
+                             .scen().set_bit()
+                        });
+                    });
+
+                    Ok(())
+                }
+
+                /// Disables smartcard mode and stops the card clock
+                pub fn disable_smartcard(&mut self) {
+                    self.with_disabled(|usart| {
+                        usart.cr2.modify(|_,w| w.clken().clear_bit());
+                        usart.cr3.modify(|_,w| w.scen().clear_bit().nack().clear_bit());
+                    });
+                }
+
+                /// Receives the next character of the Answer To Reset of the card
+                ///
+                /// Returns `WouldBlock` until a character is received, and `None` once the ATR
+                /// ended, i.e. no character was received for 9600 bits (the maximum initial waiting
+                /// time) after the last one. The receiver timeout only runs once a character has
+                /// been received: the caller gives up if the card doesn't answer within 40000 card
+                /// clock cycles after its reset (ISO 7816-3). A reception error also ends the ATR.
+                ///
+                /// ```rust
+                /// let mut len = 0;
+                /// while let Some(byte) = block!(serial.read_atr())? {
+                ///     atr[len] = byte;
+                ///     len += 1;
+                /// }
+                /// ```
+                pub fn read_atr(&mut self) -> nb::Result<Option<u8>, Error> {
+                    if self.usart.cr2.read().rtoen().bit_is_clear() {
+                        // Armed before the first character, so that it counts from its stop bit
+                        self.usart.rtor.write(|w| unsafe { w.rto().bits(9_600) });
+                        self.usart.icr.write(|w| w.rtocf().set_bit());
+                        self.usart.cr2.modify(|_,w| w.rtoen().set_bit());
+                    }
+
+                    let result = match Rx::<$USARTX>::read_word(&mut self.errors) {
+                        Ok(word) => return Ok(Some(word as u8)),
+                        Err(nb::Error::WouldBlock) => match self.usart.isr.read().rtof().bit_is_set() {
+                            true => Ok(None),
+                            false => return Err(nb::Error::WouldBlock),
+                        },
+                        Err(nb::Error::Other(error)) => Err(nb::Error::Other(error)),
+                    };
+
+                    // The ATR ended, the next call waits for a new one
+                    self.usart.cr2.modify(|_,w| w.rtoen().clear_bit());
+                    self.usart.icr.write(|w| w.rtocf().set_bit());
+                    result
+                }
+            }
+        )+
+    }
+}
+
+synchronous! {
+    USART1: (usart1, usart1_synchronous, APB2),
+    USART2: (usart2, usart2_synchronous, APB1),
+    USART3: (usart3, usart3_synchronous, APB1),
+}
+
+// UART4, UART5 and LPUART1 have no smartcard mode
+macro_rules! no_smartcard {
+    ($($USARTX:ident,)+) => {
+        $(
+            impl Tx<$USARTX> {
+                fn take_nack(&mut self) -> bool {
+                    false
+                }
+            }
+        )+
+    }
+}
+
+no_smartcard! {
+    UART4,
+    UART5,
+    LPUART1,
+}
+
+pins!(LPUART1);
+
+impl<PINS> Serial<LPUART1, PINS> {
+    /// Configures the LPUART1 peripheral to provide asynchronous serial communication
+    ///
+    /// `config` is either a `Config` or a `Bps` for 8N1. Its oversampling must be `Over16` and its
+    /// stop bits `Stop1` or `Stop2`. Clocked by the LSE, baud rates up to 9600 can be used; a LSE
+    /// or HSI16 kernel clock is required to wake up from Stop mode.
+    ///
+    /// Fails without touching the peripheral if the baud rate can not be achieved within the
+    /// tolerance of `config`, or with `BaudRateError::Unsupported` for other stop bits or
+    /// oversampling.
+    pub fn lpuart1<C>(
+        lpuart: LPUART1,
+        pins: PINS,
+        config: C,
+        clock: USARTClkSource,
+        apb: &mut APB1,
+        ccipr: &mut CCIPR,
+    ) -> Result<Self, BaudRateError>
+    where
+        PINS: Pins<LPUART1>,
+        C: Into<Config>,
+    {
+        let (clk_f, sel_bits) = match clock {
+            USARTClkSource::PCLK(c)   => (c.freq(), 0b00),
+            USARTClkSource::SYSCLK(f) => (f.into(), 0b01),
+            USARTClkSource::HSI16(c)  => (c.freq(), 0b10),
+            USARTClkSource::LSE       => (32_768,   0b11),
+        };
+        let config = config.into();
+        check_lpuart_config(&config)?;
+        let baud = Baud::lpuart(clk_f, config.baud_rate)?.within(config.baud_tolerance_ppm)?;
+
+        ccipr.inner().modify(|_,w| unsafe { w.lpuart1sel().bits(sel_bits) });
+
+        apb.enr2().modify(|_, w| w.lpuart1en().set_bit());
+        while apb.enr2().read().lpuart1en().bit_is_clear() {}
+
+        lpuart.cr1.modify(|_,w| w.ue().clear_bit());
+        // configuration bits can only be written when the peripheral is disabled
+
+        apply_lpuart_config(&lpuart, &config, &baud);
+        lpuart.cr3.modify(|_,w| w.hdsel().clear_bit());
+        pins.setup(&lpuart);
+
+        lpuart.cr1.modify(|_,w| w.ue().set_bit());
+
+        while lpuart.isr.read().teack().bit_is_clear() {}
+        while lpuart.isr.read().reack().bit_is_clear() {}
+
+        Ok(Serial {
+            usart: lpuart,
+            pins,
+            clk_f,
+            errors: ErrorCounters::default(),
+            lin_header: lin::Header::Idle,
+        })
+    }
+
+    /// Changes the frame format and baud rate
+    ///
+    /// See `Serial::lpuart1`.
+    pub fn reconfigure(&mut self, config: Config) -> Result<(), BaudRateError> {
+        check_lpuart_config(&config)?;
+        let baud = Baud::lpuart(self.clk_f, config.baud_rate)?.within(config.baud_tolerance_ppm)?;
+
+        self.with_disabled(|lpuart| {
+            apply_lpuart_config(lpuart, &config, &baud);
+        });
+
+        Ok(())
+    }
+
+    /// Enables wakeup from Stop mode on `event`
+    ///
+    /// The wakeup interrupt is enabled, the core is woken up through EXTI line
+    /// `Line::Lpuart1Wakeup` which has to be unmasked. The kernel clock must be the LSE or HSI16.
+    pub fn enable_wakeup(&mut self, event: WakeupEvent) {
+        let wus = match event {
+            WakeupEvent::AddressMatch(_) => 0b00,
+            WakeupEvent::StartBit => 0b10,
+            WakeupEvent::Rxne => 0b11,
+        };
+
+        // WUS and ADD can only be written when the peripheral is disabled
+        self.with_disabled(|lpuart| {
+            if let WakeupEvent::AddressMatch(address) = event {
+                lpuart.cr2.modify(|_,w| unsafe {
+                    w.addm7().set_bit()
+                     .add0_3().bits(address & 0xF)
+                     .add4_7().bits((address >> 4) & 0x7)
+                });
+            }
+            lpuart.cr3.modify(|_,w| unsafe { w.wus().bits(wus).wufie().set_bit() });
+            lpuart.cr1.modify(|_,w| w.uesm().set_bit());
+        });
+    }
+
+    /// Disables wakeup from Stop mode
+    pub fn disable_wakeup(&mut self) {
+        self.usart.cr1.modify(|_,w| w.uesm().clear_bit());
+        self.usart.cr3.modify(|_,w| w.wufie().clear_bit());
+    }
+
+    /// Returns whether the peripheral woke up the core, and clears the flag
+    pub fn clear_wakeup(&mut self) -> bool {
+        let woken = self.usart.isr.read().wuf().bit_is_set();
+        self.usart.icr.write(|w| w.wucf().set_bit());
+        woken
+    }
+}
+
+common!(LPUART1, rx: dma2::C7, tx: dma2::C6);
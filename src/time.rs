@@ -2,7 +2,7 @@
 macro_rules! impl_struct {
     ($($name:ident,)+) => {
         $(
-            #[derive(Clone, Copy)]
+            #[derive(Clone, Copy, Debug, PartialEq)]
             pub struct $name(pub u32);
             impl Into<$name> for u32 {
                 fn into(self) -> $name {
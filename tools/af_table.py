@@ -0,0 +1,131 @@
+#!/usr/bin/env python3
+"""Generates src/gpio/af_table.rs from af_table.csv
+
+Each line of the CSV is a cell of the alternate function tables of the datasheets (DS10198 for the
+STM32L476xx/486xx, DS11585 for the STM32L496xx/4A6xx): the pin, the alternate function, the
+signals as named by the datasheet (separated by '/') and the dies the cell applies to (`all`,
+`L476` or `L496`). Only the signals of the peripherals the HAL describes are listed.
+
+The pins are grouped by the chip features they exist with, see src/gpio/ports.rs.
+
+Usage: python3 tools/af_table.py
+"""
+
+import csv
+import os
+import re
+import sys
+
+ROOT = os.path.join(os.path.dirname(os.path.abspath(__file__)), "..")
+INPUT = os.path.join(ROOT, "tools", "af_table.csv")
+OUTPUT = os.path.join(ROOT, "src", "gpio", "af_table.rs")
+
+PACKAGES = ["LQFP64", "LQFP100", "LQFP144", "UFBGA169"]
+DIES = {
+    "all": PACKAGES,
+    "L476": ["LQFP64", "LQFP100", "LQFP144"],
+    "L496": PACKAGES,
+}
+
+SERIAL = {"TX": "TxPin", "RX": "RxPin", "CTS": "CtsPin", "RTS_DE": "RtsPin", "CK": "CkPin"}
+TIM = {
+    "CH1": "Ch1Pin", "CH2": "Ch2Pin", "CH3": "Ch3Pin", "CH4": "Ch4Pin",
+    "CH1N": "Ch1NPin", "CH2N": "Ch2NPin", "CH3N": "Ch3NPin",
+    "ETR": "EtrPin", "BKIN": "BkinPin", "BKIN2": "Bkin2Pin",
+}
+
+
+def packages(pin):
+    """Returns the packages `pin` is bonded out in, or None if it is in all of them"""
+    port, i = pin[1], int(pin[2:])
+    if port in "ABC":
+        return None
+    if port == "D":
+        return PACKAGES if i == 2 else PACKAGES[1:]
+    if port == "E":
+        return PACKAGES[1:]
+    if port in "FG":
+        return PACKAGES[2:]
+    if port == "H" and i in (0, 1):
+        return PACKAGES
+    if port == "H" and i != 3:
+        return PACKAGES[3:]
+    sys.exit("{} has no alternate functions".format(pin))
+
+
+def signal(name):
+    """Returns the marker trait of a datasheet signal name"""
+    rules = [
+        (r"((?:LP)?U(?:S)?ART\d)_(TX|RX|CTS|RTS_DE|CK)$", lambda m: "serial::{}<{}>".format(SERIAL[m.group(2)], m.group(1))),
+        (r"(SPI\d)_(SCK|MISO|MOSI|NSS)$", lambda m: "spi::{}Pin<{}>".format(m.group(2).capitalize(), m.group(1))),
+        (r"(I2C[123])_(SCL|SDA|SMBA)$", lambda m: "i2c::{}Pin<{}>".format(m.group(2).capitalize(), m.group(1))),
+        (r"(TIM\d+)_(CH\dN?|ETR|BKIN2?)$", lambda m: "tim::{}<{}>".format(TIM[m.group(2)], m.group(1))),
+        (r"(SAI\d)_(MCLK|FS|SCK|SD)_([AB])$", lambda m: "sai::{}Pin<{}, Block{}>".format(m.group(2).capitalize(), m.group(1), m.group(3))),
+        (r"QUADSPI_(?:BK1_)?(CLK|NCS|IO\d)$", lambda m: "qspi::{}Pin<QUADSPI>".format(m.group(1).capitalize())),
+        (r"SDMMC1_(CK|CMD|D\d)$", lambda m: "sdmmc::{}Pin<SDMMC>".format(m.group(1).capitalize())),
+        (r"CAN1_(TX|RX)$", lambda m: "can::{}Pin<CAN>".format(m.group(1).capitalize())),
+        (r"OTG_FS_(DM|DP|ID|SOF|NOE)$", lambda m: "usb::{}Pin<OTG_FS_GLOBAL>".format(m.group(1).capitalize())),
+        (r"LCD_(COM|SEG)\d+$", lambda m: "lcd::{}Pin<LCD>".format(m.group(1).capitalize())),
+    ]
+    for pattern, to_trait in rules:
+        m = re.match(pattern, name)
+        if m:
+            return to_trait(m)
+    sys.exit("unknown signal {}".format(name))
+
+
+def cfg(die, pkgs):
+    """Returns the `cfg` attribute of the pins of a die and packages, or None"""
+    features = []
+    if die != "all":
+        features.append('feature = "{}"'.format(die))
+    if pkgs is not None:
+        pkgs = ['feature = "{}"'.format(p) for p in pkgs if p in DIES[die]]
+        features.append(pkgs[0] if len(pkgs) == 1 else "any({})".format(", ".join(pkgs)))
+    if not features:
+        return None
+    return "#[cfg({})]".format(features[0] if len(features) == 1 else "all({})".format(", ".join(features)))
+
+
+def main():
+    blocks = {}
+    with open(INPUT) as f:
+        for row in csv.DictReader(f):
+            pin, af, die = row["pin"], int(row["af"][2:]), row["dies"]
+            if die not in DIES:
+                sys.exit("unknown die {}".format(die))
+            traits = []
+            for name in row["signals"].split("/"):
+                trait = signal(name)
+                if trait not in traits:
+                    traits.append(trait)
+            pkgs = packages(pin)
+            if pkgs is not None and die != "all" and all(p in pkgs for p in DIES[die]):
+                # e.g. PD2 is bonded out in all the packages of the STM32L476xx
+                pkgs = None
+            attr = cfg(die, pkgs)
+            # Blocks of a die from the most to the least available pins
+            count = len(PACKAGES) + 1 if pkgs is None else len(pkgs)
+            lines = blocks.setdefault((list(DIES).index(die), -count, attr), [])
+            lines.append(((pin[1], int(pin[2:]), af), "{}, AF{}: [{}];".format(pin, af, ", ".join(traits))))
+
+    out = ["// Generated by tools/af_table.py from tools/af_table.csv, do not edit"]
+    for key in sorted(blocks, key=lambda k: (k[0], k[1], k[2] or "")):
+        out.append("")
+        if key[2] is not None:
+            out.append(key[2])
+        out.append("af_table! {")
+        port = None
+        for (sort_key, line) in sorted(blocks[key]):
+            if port is not None and sort_key[0] != port:
+                out.append("")
+            port = sort_key[0]
+            out.append("    " + line)
+        out.append("}")
+
+    with open(OUTPUT, "w") as f:
+        f.write("\n".join(out) + "\n")
+
+
+if __name__ == "__main__":
+    main()